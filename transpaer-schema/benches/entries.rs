@@ -0,0 +1,127 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+use transpaer_schema as schema;
+
+const PRODUCTS: usize = 10_000;
+
+fn substrate() -> schema::Substrate {
+    let products = (0..PRODUCTS)
+        .map(|i| schema::CatalogProduct {
+            availability: None,
+            categorisation: Some(schema::ProductCategorisation {
+                categories: vec![schema::ProductCategory("food".to_owned())],
+            }),
+            description: Some(format!("Description of product {i}")),
+            id: format!("product-{i}"),
+            ids: schema::ProductIds {
+                ean: Some(vec![format!("{i:013}")]),
+                gtin: None,
+                wiki: Some(vec![format!("Q{i}")]),
+            },
+            images: vec![format!("https://example.com/{i}.png")],
+            names: vec![format!("Product {i}")],
+            origins: None,
+            related: None,
+            shopping: None,
+        })
+        .collect();
+    schema::Substrate {
+        meta: schema::Meta {
+            authors: Vec::new(),
+            creation_timestamp: None,
+            description: None,
+            title: "benchmark".to_owned(),
+            valid_from: None,
+            valid_to: None,
+            variant: schema::ProviderVariant::Cataloger,
+            version: "0.0.1".to_owned(),
+        },
+        data: schema::Data::Cataloger(schema::CatalogerData {
+            cataloger: schema::AboutCataloger {
+                description: None,
+                id: "cataloger".to_owned(),
+                name: "Cataloger".to_owned(),
+                variant: schema::CatalogVariant::Database,
+                website: "https://example.com".to_owned(),
+            },
+            producers: Vec::new(),
+            products,
+        }),
+    }
+}
+
+fn serialize(c: &mut Criterion) {
+    let substrate = substrate();
+    let schema::Data::Cataloger(data) = &substrate.data else {
+        unreachable!()
+    };
+
+    let mut group = c.benchmark_group("serialize");
+    group.bench_function("cloned", |b| {
+        b.iter(|| {
+            let mut buffer = Vec::new();
+            for product in &data.products {
+                let entry = schema::CatalogEntry::Product(product.clone());
+                serde_json::to_writer(&mut buffer, &entry).unwrap();
+            }
+            buffer
+        })
+    });
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            let mut buffer = Vec::new();
+            for product in &data.products {
+                let entry = schema::CatalogEntryRef::Product(product);
+                serde_json::to_writer(&mut buffer, &entry).unwrap();
+            }
+            buffer
+        })
+    });
+    group.finish();
+}
+
+fn save_and_iterate(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join(format!("transpaer-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let substrate = substrate();
+
+    let jsonl = dir.join("substrate.jsonl");
+    c.bench_function("save_jsonl", |b| b.iter(|| substrate.save(&jsonl).unwrap()));
+
+    let yaml = dir.join("substrate.yaml");
+    substrate.save(&yaml).unwrap();
+    let mut group = c.benchmark_group("iterate_yaml");
+    group.bench_function("moved", |b| {
+        b.iter_batched(
+            || schema::read::iter_file(&yaml).unwrap(),
+            |entries| match entries {
+                schema::read::FileIterVariant::Catalog(iter) => iter.for_each(|entry| {
+                    black_box(entry.unwrap());
+                }),
+                _ => unreachable!(),
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    // Baseline of yielding a clone of each entry of the parsed document.
+    group.bench_function("cloned", |b| {
+        b.iter_batched(
+            || schema::read::read_substrate(&yaml).unwrap(),
+            |substrate| match substrate.data {
+                schema::Data::Cataloger(data) => {
+                    for product in &data.products {
+                        black_box(schema::CatalogEntry::Product(product.clone()));
+                    }
+                }
+                _ => unreachable!(),
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+criterion_group!(benches, serialize, save_and_iterate);
+criterion_main!(benches);
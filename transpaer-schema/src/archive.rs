@@ -0,0 +1,239 @@
+use std::{
+    io::{BufRead, Read},
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use snafu::prelude::*;
+
+use crate::{
+    defs, errors,
+    read::{self, FileIter, ReadOptions},
+};
+
+/// Supported archive formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Gzip-compressed tar, with the `.tar.gz` or `.tgz` extension.
+    TarGz,
+
+    /// Zip, with the `.zip` extension.
+    Zip,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// A substrate read from an archive.
+pub struct ArchiveMember {
+    pub archive: PathBuf,
+
+    /// Path of the substrate inside the archive.
+    pub member: PathBuf,
+
+    /// The opened substrate.
+    ///
+    /// Errors of its entries name the member as a path inside the archive path.
+    pub file: FileIter,
+}
+
+type RawMember = Result<(PathBuf, Vec<u8>), errors::ArchiveError>;
+
+enum Inner {
+    /// Reads the archive on a separate thread, as tar entries borrow the archive they are read
+    /// from, and receives the members one by one.
+    Tar { receiver: mpsc::Receiver<RawMember> },
+
+    /// Reads the member with the given index next.
+    Zip {
+        archive: zip::ZipArchive<std::fs::File>,
+        index: usize,
+    },
+}
+
+/// Substrates in an archive, see `read_archive`.
+pub struct ArchiveMembers {
+    archive: PathBuf,
+    options: ReadOptions,
+    inner: Inner,
+}
+
+fn member_error(source: std::io::Error, archive: &Path, member: PathBuf) -> errors::ArchiveError {
+    errors::ArchiveError::Member {
+        source: errors::ReadError::Io {
+            source,
+            path: archive.join(&member),
+        },
+        archive: archive.to_owned(),
+        member,
+    }
+}
+
+fn read_tar_entry<R: Read>(
+    entry: std::io::Result<tar::Entry<'_, R>>,
+    archive: &Path,
+) -> Result<Option<(PathBuf, Vec<u8>)>, errors::ArchiveError> {
+    let mut entry = entry.context(errors::archive::IoSnafu { archive })?;
+    if !entry.header().entry_type().is_file() {
+        return Ok(None);
+    }
+    let member = entry
+        .path()
+        .context(errors::archive::IoSnafu { archive })?
+        .into_owned();
+    if defs::get_extension(&member).is_none() {
+        return Ok(None);
+    }
+    let mut contents = Vec::new();
+    match entry.read_to_end(&mut contents) {
+        Ok(_) => Ok(Some((member, contents))),
+        Err(err) => Err(member_error(err, archive, member)),
+    }
+}
+
+fn read_tar(file: std::fs::File, archive: PathBuf, sender: mpsc::SyncSender<RawMember>) {
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let entries = match tar.entries() {
+        Ok(entries) => entries,
+        Err(source) => {
+            let _ = sender.send(Err(errors::ArchiveError::Io { source, archive }));
+            return;
+        }
+    };
+    for entry in entries {
+        if let Some(member) = read_tar_entry(entry, &archive).transpose() {
+            // The rest of the stream cannot be trusted after an error.
+            let failed = member.is_err();
+            if sender.send(member).is_err() || failed {
+                return;
+            }
+        }
+    }
+}
+
+impl ArchiveMembers {
+    fn next_zip(
+        zip: &mut zip::ZipArchive<std::fs::File>,
+        index: &mut usize,
+        archive: &Path,
+    ) -> Option<RawMember> {
+        while *index < zip.len() {
+            *index += 1;
+            let mut file = match zip.by_index(*index - 1) {
+                Ok(file) => file,
+                Err(err) => return Some(Err(err).context(errors::archive::ZipSnafu { archive })),
+            };
+            let member = PathBuf::from(file.name());
+            if !file.is_file() || defs::get_extension(&member).is_none() {
+                continue;
+            }
+            let mut contents = Vec::new();
+            return Some(match file.read_to_end(&mut contents) {
+                Ok(_) => Ok((member, contents)),
+                Err(err) => Err(member_error(err, archive, member)),
+            });
+        }
+        None
+    }
+
+    fn next_raw(&mut self) -> Option<RawMember> {
+        match &mut self.inner {
+            Inner::Tar { receiver } => receiver.recv().ok(),
+            Inner::Zip { archive, index } => Self::next_zip(archive, index, &self.archive),
+        }
+    }
+
+    /// Parses the member reusing the logic of `read::open_file`.
+    fn open(
+        &self,
+        member: &Path,
+        contents: Vec<u8>,
+    ) -> Result<Option<FileIter>, errors::ReadError> {
+        let path = self.archive.join(member);
+        match defs::get_extension(member) {
+            Some(defs::SubstrateExtension::Yaml) => {
+                let contents = String::from_utf8(contents)
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+                    .context(errors::read::IoSnafu { path: &path })?;
+                read::parse_yaml_content(&contents, &path, &self.options)
+            }
+            Some(defs::SubstrateExtension::JsonLines) => {
+                let reader: Box<dyn BufRead + Send> = Box::new(std::io::Cursor::new(contents));
+                read::parse_lines(reader.lines(), &path, &self.options)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl std::iter::Iterator for ArchiveMembers {
+    type Item = Result<ArchiveMember, errors::ArchiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (member, contents) = match self.next_raw()? {
+                Ok(raw) => raw,
+                Err(err) => return Some(Err(err)),
+            };
+            match self.open(&member, contents) {
+                Ok(Some(file)) => {
+                    return Some(Ok(ArchiveMember {
+                        archive: self.archive.clone(),
+                        member,
+                        file,
+                    }))
+                }
+                Ok(None) => continue,
+                Err(source) => {
+                    return Some(Err(errors::ArchiveError::Member {
+                        source,
+                        archive: self.archive.clone(),
+                        member,
+                    }))
+                }
+            }
+        }
+    }
+}
+
+/// Opens a `.tar.gz` or `.zip` archive for iterating over the substrates inside it without
+/// extracting them to disk.
+///
+/// Members with an unsupported extension and substrates skipped according to the options are
+/// passed over. Each member is decompressed into memory before being parsed.
+pub fn read_archive(
+    path: &Path,
+    options: &ReadOptions,
+) -> Result<ArchiveMembers, errors::ArchiveError> {
+    let format = ArchiveFormat::from_path(path)
+        .context(errors::archive::UnsupportedFormatSnafu { archive: path })?;
+    let file = std::fs::File::open(path).context(errors::archive::IoSnafu { archive: path })?;
+    let inner = match format {
+        ArchiveFormat::TarGz => {
+            let (sender, receiver) = mpsc::sync_channel(1);
+            let archive = path.to_owned();
+            std::thread::spawn(move || read_tar(file, archive, sender));
+            Inner::Tar { receiver }
+        }
+        ArchiveFormat::Zip => {
+            let archive =
+                zip::ZipArchive::new(file).context(errors::archive::ZipSnafu { archive: path })?;
+            Inner::Zip { archive, index: 0 }
+        }
+    };
+    Ok(ArchiveMembers {
+        archive: path.to_owned(),
+        options: options.clone(),
+        inner,
+    })
+}
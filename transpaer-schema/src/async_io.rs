@@ -0,0 +1,347 @@
+use std::{
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{stream, Stream};
+use serde::{de::DeserializeOwned, Serialize};
+use snafu::prelude::*;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    data::{CatalogEntryRef, ProducerEntryRef, ReviewEntryRef},
+    defs, errors,
+    read::{
+        self, EntryDefinitions, FileIter, FileIterVariant, ReadOptions, CATALOG_DEFINITIONS,
+        PRODUCER_DEFINITIONS, REVIEW_DEFINITIONS,
+    },
+    AboutCataloger, AboutProducer, AboutReviewer, CatalogEntry, Meta, ProducerEntry,
+    ProviderVariant, ReviewEntry,
+};
+
+type BoxedEntries<T> = Pin<Box<dyn Stream<Item = Result<T, errors::ReadError>> + Send>>;
+
+/// Entries of a substrate read asynchronously, next to the header describing their provider.
+pub struct EntryStream<A, T> {
+    about: A,
+    entries: BoxedEntries<T>,
+}
+
+impl<A, T> EntryStream<A, T> {
+    /// Returns the header describing the provider of the entries.
+    pub fn about(&self) -> &A {
+        &self.about
+    }
+}
+
+impl<A: Unpin, T> Stream for EntryStream<A, T> {
+    type Item = Result<T, errors::ReadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().entries.as_mut().poll_next(cx)
+    }
+}
+
+pub type CatalogStream = EntryStream<AboutCataloger, CatalogEntry>;
+pub type ProducerStream = EntryStream<AboutProducer, ProducerEntry>;
+pub type ReviewStream = EntryStream<AboutReviewer, ReviewEntry>;
+
+pub enum StreamVariant {
+    Catalog(CatalogStream),
+    Producer(ProducerStream),
+    Review(ReviewStream),
+}
+
+/// A substrate opened by `open_file` or `read_lines`.
+pub struct FileStream {
+    pub meta: Meta,
+
+    /// `false` if the substrate is not valid at `ReadOptions::valid_at`.
+    pub is_valid: bool,
+
+    pub entries: StreamVariant,
+}
+
+impl From<FileIter> for FileStream {
+    /// Wraps entries that are already in memory or are read without blocking for long.
+    fn from(file: FileIter) -> Self {
+        fn wrap<A, T>(
+            about: A,
+            iter: impl Iterator<Item = Result<T, errors::ReadError>> + Send + 'static,
+        ) -> EntryStream<A, T> {
+            EntryStream {
+                about,
+                entries: Box::pin(stream::iter(iter)),
+            }
+        }
+
+        let entries = match file.entries {
+            FileIterVariant::Catalog(iter) => {
+                StreamVariant::Catalog(wrap(iter.about().clone(), iter))
+            }
+            FileIterVariant::Producer(iter) => {
+                StreamVariant::Producer(wrap(iter.about().clone(), iter))
+            }
+            FileIterVariant::Review(iter) => {
+                StreamVariant::Review(wrap(iter.about().clone(), iter))
+            }
+        };
+        Self {
+            meta: file.meta,
+            is_valid: file.is_valid,
+            entries,
+        }
+    }
+}
+
+async fn next_line<R: AsyncBufRead + Unpin>(
+    lines: &mut tokio::io::Lines<R>,
+    path: &Path,
+) -> Result<Option<String>, errors::ReadError> {
+    lines
+        .next_line()
+        .await
+        .context(errors::read::IoSnafu { path })
+}
+
+fn lines_stream<A, T, R>(
+    about: A,
+    lines: tokio::io::Lines<R>,
+    path: PathBuf,
+    definitions: Option<EntryDefinitions>,
+) -> EntryStream<A, T>
+where
+    T: DeserializeOwned + Send + 'static,
+    R: AsyncBufRead + Unpin + Send + 'static,
+{
+    let entries = stream::unfold((lines, path), move |(mut lines, path)| async move {
+        let entry = match next_line(&mut lines, &path).await {
+            Ok(Some(line)) => read::parse_entry(&line, definitions, &path),
+            Ok(None) => return None,
+            Err(err) => Err(err),
+        };
+        Some((entry, (lines, path)))
+    });
+    EntryStream {
+        about,
+        entries: Box::pin(entries),
+    }
+}
+
+/// Reads a JSON lines substrate from an asynchronous reader.
+///
+/// The `path` only names the source in errors. Returns `None` if the substrate was skipped
+/// according to the options.
+pub async fn read_lines<R>(
+    reader: R,
+    path: impl Into<PathBuf>,
+    options: &ReadOptions,
+) -> Result<Option<FileStream>, errors::ReadError>
+where
+    R: AsyncBufRead + Unpin + Send + 'static,
+{
+    let path = path.into();
+    let mut lines = reader.lines();
+
+    let Some(meta) = next_line(&mut lines, &path).await? else {
+        return Err(errors::SubstrateError::NoMeta).context(errors::read::SubstrateSnafu { path });
+    };
+    let meta: Meta = read::parse_json(&meta, options.schema("meta"), &path)?;
+    let Some(is_valid) = options.check_validity(&meta) else {
+        return Ok(None);
+    };
+
+    let Some(about) = next_line(&mut lines, &path).await? else {
+        return Err(errors::SubstrateError::NoAbout).context(errors::read::SubstrateSnafu { path });
+    };
+    let entries = match meta.variant {
+        ProviderVariant::Cataloger => {
+            let about = read::parse_json(&about, options.schema("aboutCataloger"), &path)?;
+            let definitions = options.schema(CATALOG_DEFINITIONS);
+            StreamVariant::Catalog(lines_stream(about, lines, path, definitions))
+        }
+        ProviderVariant::Producer => {
+            let about = read::parse_json(&about, options.schema("aboutProducer"), &path)?;
+            let definitions = options.schema(PRODUCER_DEFINITIONS);
+            StreamVariant::Producer(lines_stream(about, lines, path, definitions))
+        }
+        ProviderVariant::Reviewer => {
+            let about = read::parse_json(&about, options.schema("aboutReviewer"), &path)?;
+            let definitions = options.schema(REVIEW_DEFINITIONS);
+            StreamVariant::Review(lines_stream(about, lines, path, definitions))
+        }
+    };
+    Ok(Some(FileStream {
+        meta,
+        is_valid,
+        entries,
+    }))
+}
+
+/// Opens a substrate without blocking the runtime, the asynchronous counterpart of
+/// `read::open_file`.
+///
+/// JSON lines are parsed as they are read. YAML files are read as a whole and parsed at once on
+/// the blocking thread pool, so that a large document does not stall the other tasks.
+pub async fn open_file(
+    path: &Path,
+    options: &ReadOptions,
+) -> Result<Option<FileStream>, errors::ReadError> {
+    match defs::get_extension(path) {
+        Some(defs::SubstrateExtension::Yaml) => {
+            let contents = tokio::fs::read_to_string(path)
+                .await
+                .context(errors::read::IoSnafu { path })?;
+            let (path, options) = (path.to_owned(), options.clone());
+            tokio::task::spawn_blocking(move || {
+                Ok(read::parse_yaml_content(&contents, &path, &options)?.map(FileStream::from))
+            })
+            .await
+            .unwrap_or_else(|error| std::panic::resume_unwind(error.into_panic()))
+        }
+        Some(defs::SubstrateExtension::JsonLines) => {
+            let file = tokio::fs::File::open(path)
+                .await
+                .context(errors::read::IoSnafu { path })?;
+            read_lines(tokio::io::BufReader::new(file), path, options).await
+        }
+        None => Err(errors::ReadError::Substrate {
+            source: errors::SubstrateError::UnsupportedExtension,
+            path: path.to_owned(),
+        }),
+    }
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    value: &impl Serialize,
+    path: &Path,
+) -> Result<(), errors::SaveError> {
+    let mut line = serde_json::to_vec(value).context(errors::save::JsonSnafu { path })?;
+    line.push(b'\n');
+    writer
+        .write_all(&line)
+        .await
+        .context(errors::save::JsonLinesSnafu { path })
+}
+
+/// Writes a substrate as JSON lines: the `meta`, the provider header and then the entries.
+///
+/// The `path` only names the destination in errors.
+pub async fn write_lines<W, A, E>(
+    writer: &mut W,
+    path: &Path,
+    meta: &Meta,
+    about: &A,
+    entries: impl IntoIterator<Item = E>,
+) -> Result<(), errors::SaveError>
+where
+    W: AsyncWrite + Unpin,
+    A: Serialize,
+    E: Serialize,
+{
+    write_line(writer, meta, path).await?;
+    write_line(writer, about, path).await?;
+    for entry in entries {
+        write_line(writer, &entry, path).await?;
+    }
+    writer
+        .flush()
+        .await
+        .context(errors::save::JsonLinesSnafu { path })
+}
+
+async fn save_yaml(
+    path: &Path,
+    meta: &Meta,
+    data: &impl Serialize,
+) -> Result<(), errors::SaveError> {
+    let mut buffer = Vec::new();
+    let mut serializer = serde_yaml::Serializer::new(&mut buffer);
+    meta.serialize(&mut serializer)
+        .context(errors::save::YamlSnafu { path })?;
+    data.serialize(&mut serializer)
+        .context(errors::save::YamlSnafu { path })?;
+    drop(serializer);
+
+    tokio::fs::write(path, buffer)
+        .await
+        .context(errors::save::IoSnafu { path })
+}
+
+async fn save_lines<A: Serialize, E: Serialize>(
+    path: &Path,
+    meta: &Meta,
+    about: &A,
+    entries: impl IntoIterator<Item = E>,
+) -> Result<(), errors::SaveError> {
+    let file = tokio::fs::File::create(path)
+        .await
+        .context(errors::save::IoSnafu { path })?;
+    let mut writer = tokio::io::BufWriter::new(file);
+    write_lines(&mut writer, path, meta, about, entries).await
+}
+
+fn unsupported_extension(path: &Path) -> Result<(), errors::SaveError> {
+    Err(errors::SubstrateError::UnsupportedExtension).context(errors::save::SubstrateSnafu { path })
+}
+
+pub async fn save_cataloger(
+    path: &Path,
+    meta: &Meta,
+    data: &crate::CatalogerData,
+) -> Result<(), errors::SaveError> {
+    match defs::get_extension(path) {
+        Some(defs::SubstrateExtension::Yaml) => save_yaml(path, meta, data).await,
+        Some(defs::SubstrateExtension::JsonLines) => {
+            let entries = (data.products.iter().map(CatalogEntryRef::Product))
+                .chain(data.producers.iter().map(CatalogEntryRef::Producer));
+            save_lines(path, meta, &data.cataloger, entries).await
+        }
+        None => unsupported_extension(path),
+    }
+}
+
+pub async fn save_producer(
+    path: &Path,
+    meta: &Meta,
+    data: &crate::ProducerData,
+) -> Result<(), errors::SaveError> {
+    match defs::get_extension(path) {
+        Some(defs::SubstrateExtension::Yaml) => save_yaml(path, meta, data).await,
+        Some(defs::SubstrateExtension::JsonLines) => {
+            let entries = (data.products.iter().map(ProducerEntryRef::Product))
+                .chain(data.reviewers.iter().map(ProducerEntryRef::Reviewer));
+            save_lines(path, meta, &data.producer, entries).await
+        }
+        None => unsupported_extension(path),
+    }
+}
+
+pub async fn save_reviewer(
+    path: &Path,
+    meta: &Meta,
+    data: &crate::ReviewerData,
+) -> Result<(), errors::SaveError> {
+    match defs::get_extension(path) {
+        Some(defs::SubstrateExtension::Yaml) => save_yaml(path, meta, data).await,
+        Some(defs::SubstrateExtension::JsonLines) => {
+            let entries = (data.products.iter().map(ReviewEntryRef::Product))
+                .chain(data.producers.iter().map(ReviewEntryRef::Producer));
+            save_lines(path, meta, &data.reviewer, entries).await
+        }
+        None => unsupported_extension(path),
+    }
+}
+
+impl crate::data::Substrate {
+    /// Saves the substrate without blocking the runtime, see `Substrate::save`.
+    pub async fn save_async(&self, path: &Path) -> Result<(), errors::SaveError> {
+        match &self.data {
+            crate::data::Data::Cataloger(data) => save_cataloger(path, &self.meta, data).await,
+            crate::data::Data::Producer(data) => save_producer(path, &self.meta, data).await,
+            crate::data::Data::Reviewer(data) => save_reviewer(path, &self.meta, data).await,
+        }
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+
+use crate::{
+    data::{CatalogEntry, ProducerEntry, ReviewEntry},
+    errors,
+    read::{CatalogIter, FileIterVariant, ProducerIter, ReviewIter},
+    Data,
+};
+
+/// Options for collecting entries into data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollectOptions {
+    /// Merges entities with an already collected `id` into the earlier one using their `merge`
+    /// methods, instead of keeping both.
+    pub dedup: bool,
+
+    /// Sorts the collected data.
+    pub sort: bool,
+}
+
+/// Entities in the order they were collected, optionally merged by `id`.
+///
+/// Also merges the entity lists of whole substrates in `merge`.
+pub(crate) struct Entities<T> {
+    entities: Vec<T>,
+    positions: HashMap<String, usize>,
+    dedup: bool,
+}
+
+impl<T> Entities<T> {
+    fn new(dedup: bool) -> Self {
+        Self {
+            entities: Vec::new(),
+            positions: HashMap::new(),
+            dedup,
+        }
+    }
+
+    fn push(&mut self, entity: T, id: impl Fn(&T) -> &str, merge: impl Fn(&T, &T) -> T) {
+        if !self.dedup {
+            self.entities.push(entity);
+            return;
+        }
+        match self.positions.get(id(&entity)) {
+            Some(&position) => {
+                self.entities[position] = merge(&self.entities[position], &entity);
+            }
+            None => {
+                self.positions
+                    .insert(id(&entity).to_owned(), self.entities.len());
+                self.entities.push(entity);
+            }
+        }
+    }
+
+    /// Merges two lists of entities, merging entities sharing the same `id`.
+    ///
+    /// Keeps the order of first appearance.
+    pub(crate) fn merge_by_id(
+        v1: &[T],
+        v2: &[T],
+        id: impl Fn(&T) -> &str,
+        merge: impl Fn(&T, &T) -> T,
+    ) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut entities = Self::new(true);
+        for entity in v1.iter().chain(v2) {
+            entities.push(entity.clone(), &id, &merge);
+        }
+        entities.entities
+    }
+}
+
+/// Builds `CatalogerData` from catalog entries.
+pub struct CatalogerDataBuilder {
+    cataloger: crate::AboutCataloger,
+    producers: Entities<crate::CatalogProducer>,
+    products: Entities<crate::CatalogProduct>,
+    sort: bool,
+}
+
+impl CatalogerDataBuilder {
+    pub fn new(cataloger: crate::AboutCataloger, options: CollectOptions) -> Self {
+        Self {
+            cataloger,
+            producers: Entities::new(options.dedup),
+            products: Entities::new(options.dedup),
+            sort: options.sort,
+        }
+    }
+
+    pub fn push(&mut self, entry: CatalogEntry) {
+        match entry {
+            CatalogEntry::Producer(producer) => {
+                self.producers
+                    .push(producer, |p| &p.id, crate::CatalogProducer::merge)
+            }
+            CatalogEntry::Product(product) => {
+                self.products
+                    .push(product, |p| &p.id, crate::CatalogProduct::merge)
+            }
+        }
+    }
+
+    /// Pushes all entries, stopping at the first error.
+    pub fn try_extend<E>(
+        &mut self,
+        entries: impl IntoIterator<Item = Result<CatalogEntry, E>>,
+    ) -> Result<(), E> {
+        for entry in entries {
+            self.push(entry?);
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> crate::CatalogerData {
+        let mut data = crate::CatalogerData {
+            cataloger: self.cataloger,
+            producers: self.producers.entities,
+            products: self.products.entities,
+        };
+        if self.sort {
+            data.sort();
+        }
+        data
+    }
+}
+
+impl Extend<CatalogEntry> for CatalogerDataBuilder {
+    fn extend<I: IntoIterator<Item = CatalogEntry>>(&mut self, entries: I) {
+        entries.into_iter().for_each(|entry| self.push(entry));
+    }
+}
+
+/// Builds `ProducerData` from producer entries.
+pub struct ProducerDataBuilder {
+    producer: crate::AboutProducer,
+    products: Entities<crate::ProducerProduct>,
+    reviewers: Entities<crate::ProducerReviewer>,
+    sort: bool,
+}
+
+impl ProducerDataBuilder {
+    pub fn new(producer: crate::AboutProducer, options: CollectOptions) -> Self {
+        Self {
+            producer,
+            products: Entities::new(options.dedup),
+            reviewers: Entities::new(options.dedup),
+            sort: options.sort,
+        }
+    }
+
+    pub fn push(&mut self, entry: ProducerEntry) {
+        match entry {
+            ProducerEntry::Product(product) => {
+                self.products
+                    .push(product, |p| &p.id, crate::ProducerProduct::merge)
+            }
+            ProducerEntry::Reviewer(reviewer) => {
+                self.reviewers
+                    .push(reviewer, |r| &r.id, crate::ProducerReviewer::merge)
+            }
+        }
+    }
+
+    /// Pushes all entries, stopping at the first error.
+    pub fn try_extend<E>(
+        &mut self,
+        entries: impl IntoIterator<Item = Result<ProducerEntry, E>>,
+    ) -> Result<(), E> {
+        for entry in entries {
+            self.push(entry?);
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> crate::ProducerData {
+        let mut data = crate::ProducerData {
+            producer: self.producer,
+            products: self.products.entities,
+            reviewers: self.reviewers.entities,
+        };
+        if self.sort {
+            data.sort();
+        }
+        data
+    }
+}
+
+impl Extend<ProducerEntry> for ProducerDataBuilder {
+    fn extend<I: IntoIterator<Item = ProducerEntry>>(&mut self, entries: I) {
+        entries.into_iter().for_each(|entry| self.push(entry));
+    }
+}
+
+/// Builds `ReviewerData` from review entries.
+pub struct ReviewerDataBuilder {
+    reviewer: crate::AboutReviewer,
+    producers: Entities<crate::ReviewProducer>,
+    products: Entities<crate::ReviewProduct>,
+    sort: bool,
+}
+
+impl ReviewerDataBuilder {
+    pub fn new(reviewer: crate::AboutReviewer, options: CollectOptions) -> Self {
+        Self {
+            reviewer,
+            producers: Entities::new(options.dedup),
+            products: Entities::new(options.dedup),
+            sort: options.sort,
+        }
+    }
+
+    pub fn push(&mut self, entry: ReviewEntry) {
+        match entry {
+            ReviewEntry::Producer(producer) => {
+                self.producers
+                    .push(producer, |p| &p.id, crate::ReviewProducer::merge)
+            }
+            ReviewEntry::Product(product) => {
+                self.products
+                    .push(product, |p| &p.id, crate::ReviewProduct::merge)
+            }
+        }
+    }
+
+    /// Pushes all entries, stopping at the first error.
+    pub fn try_extend<E>(
+        &mut self,
+        entries: impl IntoIterator<Item = Result<ReviewEntry, E>>,
+    ) -> Result<(), E> {
+        for entry in entries {
+            self.push(entry?);
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> crate::ReviewerData {
+        let mut data = crate::ReviewerData {
+            reviewer: self.reviewer,
+            producers: self.producers.entities,
+            products: self.products.entities,
+        };
+        if self.sort {
+            data.sort();
+        }
+        data
+    }
+}
+
+impl Extend<ReviewEntry> for ReviewerDataBuilder {
+    fn extend<I: IntoIterator<Item = ReviewEntry>>(&mut self, entries: I) {
+        entries.into_iter().for_each(|entry| self.push(entry));
+    }
+}
+
+impl crate::CatalogerData {
+    /// Collects the entries under the given header, stopping at the first error.
+    pub fn from_entries<E>(
+        cataloger: crate::AboutCataloger,
+        entries: impl IntoIterator<Item = Result<CatalogEntry, E>>,
+        options: CollectOptions,
+    ) -> Result<Self, E> {
+        let mut builder = CatalogerDataBuilder::new(cataloger, options);
+        builder.try_extend(entries)?;
+        Ok(builder.build())
+    }
+}
+
+impl crate::ProducerData {
+    /// Collects the entries under the given header, stopping at the first error.
+    pub fn from_entries<E>(
+        producer: crate::AboutProducer,
+        entries: impl IntoIterator<Item = Result<ProducerEntry, E>>,
+        options: CollectOptions,
+    ) -> Result<Self, E> {
+        let mut builder = ProducerDataBuilder::new(producer, options);
+        builder.try_extend(entries)?;
+        Ok(builder.build())
+    }
+}
+
+impl crate::ReviewerData {
+    /// Collects the entries under the given header, stopping at the first error.
+    pub fn from_entries<E>(
+        reviewer: crate::AboutReviewer,
+        entries: impl IntoIterator<Item = Result<ReviewEntry, E>>,
+        options: CollectOptions,
+    ) -> Result<Self, E> {
+        let mut builder = ReviewerDataBuilder::new(reviewer, options);
+        builder.try_extend(entries)?;
+        Ok(builder.build())
+    }
+}
+
+impl CatalogIter {
+    /// Reads the remaining entries into data with the header of the substrate.
+    pub fn collect_data(
+        self,
+        options: CollectOptions,
+    ) -> Result<crate::CatalogerData, errors::ReadError> {
+        crate::CatalogerData::from_entries(self.about().clone(), self, options)
+    }
+}
+
+impl ProducerIter {
+    /// Reads the remaining entries into data with the header of the substrate.
+    pub fn collect_data(
+        self,
+        options: CollectOptions,
+    ) -> Result<crate::ProducerData, errors::ReadError> {
+        crate::ProducerData::from_entries(self.about().clone(), self, options)
+    }
+}
+
+impl ReviewIter {
+    /// Reads the remaining entries into data with the header of the substrate.
+    pub fn collect_data(
+        self,
+        options: CollectOptions,
+    ) -> Result<crate::ReviewerData, errors::ReadError> {
+        crate::ReviewerData::from_entries(self.about().clone(), self, options)
+    }
+}
+
+impl FileIterVariant {
+    /// Reads the remaining entries into data with the header of the substrate.
+    pub fn collect_data(self, options: CollectOptions) -> Result<Data, errors::ReadError> {
+        Ok(match self {
+            FileIterVariant::Catalog(iter) => Data::Cataloger(iter.collect_data(options)?),
+            FileIterVariant::Producer(iter) => Data::Producer(iter.collect_data(options)?),
+            FileIterVariant::Review(iter) => Data::Reviewer(iter.collect_data(options)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn product(id: &str, name: &str) -> CatalogEntry {
+        CatalogEntry::Product(crate::CatalogProduct {
+            availability: None,
+            categorisation: None,
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: None,
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: vec![name.to_owned()],
+            origins: None,
+            related: None,
+            shopping: None,
+        })
+    }
+
+    fn cataloger() -> crate::AboutCataloger {
+        crate::AboutCataloger {
+            description: None,
+            id: "cataloger".to_owned(),
+            name: "Cataloger".to_owned(),
+            variant: crate::CatalogVariant::Database,
+            website: "https://example.com".to_owned(),
+        }
+    }
+
+    fn entries() -> Vec<Result<CatalogEntry, &'static str>> {
+        vec![
+            Ok(product("b", "B")),
+            Ok(product("a", "A")),
+            Ok(product("b", "Bee")),
+        ]
+    }
+
+    fn products(data: &crate::CatalogerData) -> Vec<(&str, &[String])> {
+        data.products
+            .iter()
+            .map(|p| (p.id.as_str(), p.names.as_slice()))
+            .collect()
+    }
+
+    #[test]
+    fn test_collect() {
+        let data =
+            crate::CatalogerData::from_entries(cataloger(), entries(), CollectOptions::default())
+                .unwrap();
+        assert_eq!(data.cataloger, cataloger());
+        let ids: Vec<_> = products(&data).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, ["b", "a", "b"]);
+
+        let options = CollectOptions {
+            dedup: true,
+            sort: true,
+        };
+        let data = crate::CatalogerData::from_entries(cataloger(), entries(), options).unwrap();
+        let names = ["B".to_owned(), "Bee".to_owned()];
+        assert_eq!(
+            products(&data),
+            [("a", &["A".to_owned()][..]), ("b", &names[..])]
+        );
+    }
+
+    #[test]
+    fn test_collect_error() {
+        let mut entries = entries();
+        entries.insert(1, Err("broken"));
+        let result =
+            crate::CatalogerData::from_entries(cataloger(), entries, CollectOptions::default());
+        assert_eq!(result.unwrap_err(), "broken");
+    }
+
+    #[test]
+    fn test_merge_by_id() {
+        let entity = |id: &str, names: &[&str]| crate::ProducerReviewer {
+            description: None,
+            id: id.to_owned(),
+            names: names.iter().map(|n| n.to_string()).collect(),
+        };
+        assert_eq!(
+            Entities::merge_by_id(
+                &[entity("b", &["b1"]), entity("a", &["a1"])],
+                &[entity("c", &[]), entity("b", &["b2"])],
+                |e| &e.id,
+                crate::ProducerReviewer::merge,
+            ),
+            vec![
+                entity("b", &["b1", "b2"]),
+                entity("a", &["a1"]),
+                entity("c", &[])
+            ]
+        );
+    }
+}
@@ -2,6 +2,29 @@ use serde::{Deserialize, Serialize};
 
 use crate::models;
 
+/// Kind of an entity in a substrate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Meta,
+    Cataloger,
+    Producer,
+    Product,
+    Reviewer,
+}
+
+impl EntityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Meta => "meta",
+            Self::Cataloger => "cataloger",
+            Self::Producer => "producer",
+            Self::Product => "product",
+            Self::Reviewer => "reviewer",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum CatalogEntry {
@@ -33,6 +56,116 @@ pub enum ReviewEntry {
     Product(models::ReviewProduct),
 }
 
+/// Borrowed counterpart of `CatalogEntry`, serialized the same way.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum CatalogEntryRef<'a> {
+    #[serde(rename = "producer")]
+    Producer(&'a models::CatalogProducer),
+
+    #[serde(rename = "product")]
+    Product(&'a models::CatalogProduct),
+}
+
+/// Borrowed counterpart of `ProducerEntry`, serialized the same way.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum ProducerEntryRef<'a> {
+    #[serde(rename = "product")]
+    Product(&'a models::ProducerProduct),
+
+    #[serde(rename = "reviewer")]
+    Reviewer(&'a models::ProducerReviewer),
+}
+
+/// Borrowed counterpart of `ReviewEntry`, serialized the same way.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum ReviewEntryRef<'a> {
+    #[serde(rename = "producer")]
+    Producer(&'a models::ReviewProducer),
+
+    #[serde(rename = "product")]
+    Product(&'a models::ReviewProduct),
+}
+
+impl CatalogEntry {
+    pub fn as_ref(&self) -> CatalogEntryRef<'_> {
+        match self {
+            Self::Producer(producer) => CatalogEntryRef::Producer(producer),
+            Self::Product(product) => CatalogEntryRef::Product(product),
+        }
+    }
+}
+
+impl ProducerEntry {
+    pub fn as_ref(&self) -> ProducerEntryRef<'_> {
+        match self {
+            Self::Product(product) => ProducerEntryRef::Product(product),
+            Self::Reviewer(reviewer) => ProducerEntryRef::Reviewer(reviewer),
+        }
+    }
+}
+
+impl ReviewEntry {
+    pub fn as_ref(&self) -> ReviewEntryRef<'_> {
+        match self {
+            Self::Producer(producer) => ReviewEntryRef::Producer(producer),
+            Self::Product(product) => ReviewEntryRef::Product(product),
+        }
+    }
+}
+
+/// Removal of an entity in a delta substrate.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Tombstone {
+    pub entity: EntityKind,
+    pub id: String,
+}
+
+/// Entry of a cataloger delta: an upsert tagged like a `CatalogEntry` or a tombstone.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum CatalogDeltaEntry {
+    #[serde(rename = "producer")]
+    Producer(models::CatalogProducer),
+
+    #[serde(rename = "product")]
+    Product(models::CatalogProduct),
+
+    #[serde(rename = "delete")]
+    Delete(Tombstone),
+}
+
+/// Entry of a producer delta: an upsert tagged like a `ProducerEntry` or a tombstone.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum ProducerDeltaEntry {
+    #[serde(rename = "product")]
+    Product(models::ProducerProduct),
+
+    #[serde(rename = "reviewer")]
+    Reviewer(models::ProducerReviewer),
+
+    #[serde(rename = "delete")]
+    Delete(Tombstone),
+}
+
+/// Entry of a reviewer delta: an upsert tagged like a `ReviewEntry` or a tombstone.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum ReviewDeltaEntry {
+    #[serde(rename = "producer")]
+    Producer(models::ReviewProducer),
+
+    #[serde(rename = "product")]
+    Product(models::ReviewProduct),
+
+    #[serde(rename = "delete")]
+    Delete(Tombstone),
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Data {
     Cataloger(models::CatalogerData),
@@ -45,3 +178,22 @@ pub struct Substrate {
     pub meta: models::Meta,
     pub data: Data,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_entry_refs_serialize_like_entries() {
+        let reviewer = models::ProducerReviewer {
+            description: None,
+            id: "reviewer".to_owned(),
+            names: vec!["Reviewer".to_owned()],
+        };
+        let entry = ProducerEntry::Reviewer(reviewer);
+        assert_eq!(
+            serde_json::to_string(&entry.as_ref()).unwrap(),
+            serde_json::to_string(&entry).unwrap()
+        );
+    }
+}
@@ -28,3 +28,7 @@ pub fn get_extension(path: &std::path::Path) -> Option<SubstrateExtension> {
         None => None,
     }
 }
+
+/// The JSON schema the models are generated from.
+#[cfg(feature = "json-schema")]
+pub const JSON_SCHEMA: &str = include_str!("../schema/transpaer.json");
@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use crate::{
+    data::{CatalogDeltaEntry, ProducerDeltaEntry, ReviewDeltaEntry, Tombstone},
+    errors, AboutCataloger, AboutProducer, AboutReviewer, EntityKind, Meta,
+};
+
+/// Marks a substrate as a delta against the substrate with the given version.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DeltaOf {
+    pub base_version: String,
+}
+
+/// `Meta` of a delta substrate.
+///
+/// Serialized as a regular `Meta` with an additional `delta` field.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DeltaMeta {
+    #[serde(flatten)]
+    pub meta: Meta,
+
+    pub delta: DeltaOf,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogerDelta {
+    pub cataloger: AboutCataloger,
+    pub entries: Vec<CatalogDeltaEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProducerDelta {
+    pub producer: AboutProducer,
+    pub entries: Vec<ProducerDeltaEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReviewerDelta {
+    pub reviewer: AboutReviewer,
+    pub entries: Vec<ReviewDeltaEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum DeltaData {
+    Cataloger(CatalogerDelta),
+    Producer(ProducerDelta),
+    Reviewer(ReviewerDelta),
+}
+
+/// Changes to a substrate: entities to insert or replace and entities to remove.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DeltaSubstrate {
+    pub meta: DeltaMeta,
+    pub data: DeltaData,
+}
+
+/// Entities with their positions indexed by `id` for repeated upserts and removals.
+///
+/// Also used to apply patches.
+pub(crate) struct EntityList<T, F> {
+    entities: Vec<Option<T>>,
+    positions: HashMap<String, usize>,
+    id: F,
+}
+
+impl<T, F: Fn(&T) -> &str> EntityList<T, F> {
+    pub(crate) fn new(entities: Vec<T>, id: F) -> Self {
+        let positions = entities
+            .iter()
+            .enumerate()
+            .map(|(position, entity)| (id(entity).to_owned(), position))
+            .collect();
+        let entities = entities.into_iter().map(Some).collect();
+        Self {
+            entities,
+            positions,
+            id,
+        }
+    }
+
+    pub(crate) fn get(&self, id: &str) -> Option<&T> {
+        let position = *self.positions.get(id)?;
+        self.entities[position].as_ref()
+    }
+
+    pub(crate) fn upsert(&mut self, entity: T) {
+        let id = (self.id)(&entity).to_owned();
+        match self.positions.get(&id) {
+            Some(&position) => self.entities[position] = Some(entity),
+            None => {
+                self.positions.insert(id, self.entities.len());
+                self.entities.push(Some(entity));
+            }
+        }
+    }
+
+    /// Replaces the entity with `id` in place, also when the replacement has another `id`.
+    pub(crate) fn replace(&mut self, id: &str, entity: T) {
+        match self.positions.remove(id) {
+            Some(position) => {
+                self.positions
+                    .insert((self.id)(&entity).to_owned(), position);
+                self.entities[position] = Some(entity);
+            }
+            None => self.upsert(entity),
+        }
+    }
+
+    pub(crate) fn remove(&mut self, id: &str) {
+        if let Some(position) = self.positions.remove(id) {
+            self.entities[position] = None;
+        }
+    }
+
+    pub(crate) fn finish(self) -> Vec<T> {
+        self.entities.into_iter().flatten().collect()
+    }
+}
+
+fn unsupported(tombstone: &Tombstone) -> errors::DeltaError {
+    errors::DeltaError::UnsupportedEntity {
+        entity: tombstone.entity.as_str(),
+    }
+}
+
+/// Collects upserts of new or changed entities and tombstones of removed ones.
+fn delta_entities<T: PartialEq + Clone, E>(
+    old: &[T],
+    new: &[T],
+    id: impl Fn(&T) -> &str,
+    entity: EntityKind,
+    upsert: impl Fn(T) -> E,
+    delete: impl Fn(Tombstone) -> E,
+    entries: &mut Vec<E>,
+) {
+    let old_by_id: HashMap<&str, &T> = old.iter().map(|e| (id(e), e)).collect();
+    let new_by_id: HashMap<&str, &T> = new.iter().map(|e| (id(e), e)).collect();
+    for e in new {
+        if old_by_id.get(id(e)) != Some(&e) {
+            entries.push(upsert(e.clone()));
+        }
+    }
+    for e in old {
+        if !new_by_id.contains_key(id(e)) {
+            let id = id(e).to_owned();
+            entries.push(delete(Tombstone { entity, id }));
+        }
+    }
+}
+
+impl crate::CatalogerData {
+    /// Applies upserts and tombstones, replacing the `cataloger` with the one from the delta.
+    ///
+    /// Tombstones of entities that do not exist are ignored. Nothing is changed on error.
+    pub fn apply_delta(&mut self, delta: &CatalogerDelta) -> Result<(), errors::DeltaError> {
+        let mut producers = EntityList::new(self.producers.clone(), |p| &p.id);
+        let mut products = EntityList::new(self.products.clone(), |p| &p.id);
+        for entry in &delta.entries {
+            match entry {
+                CatalogDeltaEntry::Producer(producer) => producers.upsert(producer.clone()),
+                CatalogDeltaEntry::Product(product) => products.upsert(product.clone()),
+                CatalogDeltaEntry::Delete(tombstone) => match tombstone.entity {
+                    EntityKind::Producer => producers.remove(&tombstone.id),
+                    EntityKind::Product => products.remove(&tombstone.id),
+                    _ => return Err(unsupported(tombstone)),
+                },
+            }
+        }
+        self.producers = producers.finish();
+        self.products = products.finish();
+        self.cataloger = delta.cataloger.clone();
+        Ok(())
+    }
+
+    /// Creates the delta turning `self` into `new`.
+    pub fn delta_to(&self, new: &Self) -> CatalogerDelta {
+        let mut entries = Vec::new();
+        delta_entities(
+            &self.producers,
+            &new.producers,
+            |p| &p.id,
+            EntityKind::Producer,
+            CatalogDeltaEntry::Producer,
+            CatalogDeltaEntry::Delete,
+            &mut entries,
+        );
+        delta_entities(
+            &self.products,
+            &new.products,
+            |p| &p.id,
+            EntityKind::Product,
+            CatalogDeltaEntry::Product,
+            CatalogDeltaEntry::Delete,
+            &mut entries,
+        );
+        CatalogerDelta {
+            cataloger: new.cataloger.clone(),
+            entries,
+        }
+    }
+}
+
+impl crate::ProducerData {
+    /// Applies upserts and tombstones, replacing the `producer` with the one from the delta.
+    ///
+    /// Tombstones of entities that do not exist are ignored. Nothing is changed on error.
+    pub fn apply_delta(&mut self, delta: &ProducerDelta) -> Result<(), errors::DeltaError> {
+        let mut products = EntityList::new(self.products.clone(), |p| &p.id);
+        let mut reviewers = EntityList::new(self.reviewers.clone(), |r| &r.id);
+        for entry in &delta.entries {
+            match entry {
+                ProducerDeltaEntry::Product(product) => products.upsert(product.clone()),
+                ProducerDeltaEntry::Reviewer(reviewer) => reviewers.upsert(reviewer.clone()),
+                ProducerDeltaEntry::Delete(tombstone) => match tombstone.entity {
+                    EntityKind::Product => products.remove(&tombstone.id),
+                    EntityKind::Reviewer => reviewers.remove(&tombstone.id),
+                    _ => return Err(unsupported(tombstone)),
+                },
+            }
+        }
+        self.products = products.finish();
+        self.reviewers = reviewers.finish();
+        self.producer = delta.producer.clone();
+        Ok(())
+    }
+
+    /// Creates the delta turning `self` into `new`.
+    pub fn delta_to(&self, new: &Self) -> ProducerDelta {
+        let mut entries = Vec::new();
+        delta_entities(
+            &self.products,
+            &new.products,
+            |p| &p.id,
+            EntityKind::Product,
+            ProducerDeltaEntry::Product,
+            ProducerDeltaEntry::Delete,
+            &mut entries,
+        );
+        delta_entities(
+            &self.reviewers,
+            &new.reviewers,
+            |r| &r.id,
+            EntityKind::Reviewer,
+            ProducerDeltaEntry::Reviewer,
+            ProducerDeltaEntry::Delete,
+            &mut entries,
+        );
+        ProducerDelta {
+            producer: new.producer.clone(),
+            entries,
+        }
+    }
+}
+
+impl crate::ReviewerData {
+    /// Applies upserts and tombstones, replacing the `reviewer` with the one from the delta.
+    ///
+    /// Tombstones of entities that do not exist are ignored. Nothing is changed on error.
+    pub fn apply_delta(&mut self, delta: &ReviewerDelta) -> Result<(), errors::DeltaError> {
+        let mut producers = EntityList::new(self.producers.clone(), |p| &p.id);
+        let mut products = EntityList::new(self.products.clone(), |p| &p.id);
+        for entry in &delta.entries {
+            match entry {
+                ReviewDeltaEntry::Producer(producer) => producers.upsert(producer.clone()),
+                ReviewDeltaEntry::Product(product) => products.upsert(product.clone()),
+                ReviewDeltaEntry::Delete(tombstone) => match tombstone.entity {
+                    EntityKind::Producer => producers.remove(&tombstone.id),
+                    EntityKind::Product => products.remove(&tombstone.id),
+                    _ => return Err(unsupported(tombstone)),
+                },
+            }
+        }
+        self.producers = producers.finish();
+        self.products = products.finish();
+        self.reviewer = delta.reviewer.clone();
+        Ok(())
+    }
+
+    /// Creates the delta turning `self` into `new`.
+    pub fn delta_to(&self, new: &Self) -> ReviewerDelta {
+        let mut entries = Vec::new();
+        delta_entities(
+            &self.producers,
+            &new.producers,
+            |p| &p.id,
+            EntityKind::Producer,
+            ReviewDeltaEntry::Producer,
+            ReviewDeltaEntry::Delete,
+            &mut entries,
+        );
+        delta_entities(
+            &self.products,
+            &new.products,
+            |p| &p.id,
+            EntityKind::Product,
+            ReviewDeltaEntry::Product,
+            ReviewDeltaEntry::Delete,
+            &mut entries,
+        );
+        ReviewerDelta {
+            reviewer: new.reviewer.clone(),
+            entries,
+        }
+    }
+}
+
+impl crate::Substrate {
+    /// Applies a delta based on the current version of the substrate.
+    ///
+    /// On success the metadata is replaced with the metadata of the delta. Nothing is changed
+    /// on error.
+    pub fn apply_delta(&mut self, delta: &DeltaSubstrate) -> Result<(), errors::DeltaError> {
+        let base_version = &delta.meta.delta.base_version;
+        ensure!(
+            *base_version == self.meta.version,
+            errors::delta::VersionMismatchSnafu {
+                base_version,
+                version: &self.meta.version,
+            }
+        );
+        match (&mut self.data, &delta.data) {
+            (crate::Data::Cataloger(data), DeltaData::Cataloger(delta)) => data.apply_delta(delta),
+            (crate::Data::Producer(data), DeltaData::Producer(delta)) => data.apply_delta(delta),
+            (crate::Data::Reviewer(data), DeltaData::Reviewer(delta)) => data.apply_delta(delta),
+            _ => errors::delta::VariantMismatchSnafu.fail(),
+        }?;
+        self.meta = delta.meta.meta.clone();
+        Ok(())
+    }
+
+    /// Applies a chain of deltas, each based on the version produced by the previous one.
+    pub fn fold_deltas<'a>(
+        mut self,
+        deltas: impl IntoIterator<Item = &'a DeltaSubstrate>,
+    ) -> Result<Self, errors::DeltaError> {
+        for delta in deltas {
+            self.apply_delta(delta)?;
+        }
+        Ok(self)
+    }
+
+    /// Creates the delta turning `self` into `new`.
+    ///
+    /// Returns `None` if the provider variants differ.
+    pub fn delta_to(&self, new: &Self) -> Option<DeltaSubstrate> {
+        let data = match (&self.data, &new.data) {
+            (crate::Data::Cataloger(old), crate::Data::Cataloger(new)) => {
+                DeltaData::Cataloger(old.delta_to(new))
+            }
+            (crate::Data::Producer(old), crate::Data::Producer(new)) => {
+                DeltaData::Producer(old.delta_to(new))
+            }
+            (crate::Data::Reviewer(old), crate::Data::Reviewer(new)) => {
+                DeltaData::Reviewer(old.delta_to(new))
+            }
+            _ => return None,
+        };
+        Some(DeltaSubstrate {
+            meta: DeltaMeta {
+                meta: new.meta.clone(),
+                delta: DeltaOf {
+                    base_version: self.meta.version.clone(),
+                },
+            },
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn product(id: &str, names: &[&str]) -> crate::CatalogProduct {
+        crate::CatalogProduct {
+            availability: None,
+            categorisation: None,
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: None,
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: names.iter().map(|n| n.to_string()).collect(),
+            origins: None,
+            related: None,
+            shopping: None,
+        }
+    }
+
+    fn substrate(version: &str, products: Vec<crate::CatalogProduct>) -> crate::Substrate {
+        crate::Substrate {
+            meta: crate::Meta {
+                authors: Vec::new(),
+                creation_timestamp: None,
+                description: None,
+                title: "Title".to_owned(),
+                valid_from: None,
+                valid_to: None,
+                variant: crate::ProviderVariant::Cataloger,
+                version: version.to_owned(),
+            },
+            data: crate::Data::Cataloger(crate::CatalogerData {
+                cataloger: crate::AboutCataloger {
+                    description: None,
+                    id: "cataloger".to_owned(),
+                    name: "Cataloger".to_owned(),
+                    variant: crate::CatalogVariant::Database,
+                    website: "https://example.com".to_owned(),
+                },
+                producers: Vec::new(),
+                products,
+            }),
+        }
+    }
+
+    fn product_ids(substrate: &crate::Substrate) -> Vec<(&str, &[String])> {
+        let crate::Data::Cataloger(data) = &substrate.data else {
+            panic!("expected a cataloger substrate");
+        };
+        data.products
+            .iter()
+            .map(|p| (p.id.as_str(), p.names.as_slice()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fold_deltas() {
+        let v1 = substrate("1", vec![product("a", &["A"]), product("b", &["B"])]);
+        let v2 = substrate("2", vec![product("a", &["A2"]), product("c", &["C"])]);
+        let v3 = substrate("3", vec![product("c", &["C"]), product("d", &["D"])]);
+
+        let d2 = v1.delta_to(&v2).unwrap();
+        let d3 = v2.delta_to(&v3).unwrap();
+        let DeltaData::Cataloger(delta) = &d2.data else {
+            panic!("expected a cataloger delta");
+        };
+        assert_eq!(delta.entries.len(), 3);
+
+        let folded = v1.clone().fold_deltas([&d2, &d3]).unwrap();
+        assert_eq!(folded.meta, v3.meta);
+        assert_eq!(product_ids(&folded), product_ids(&v3));
+
+        assert_eq!(
+            v1.fold_deltas([&d3]).unwrap_err(),
+            errors::DeltaError::VersionMismatch {
+                base_version: "2".to_owned(),
+                version: "1".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_tombstones() {
+        let mut base = substrate("1", vec![product("a", &[])]);
+        let line = r#"{"type":"delete","entity":"product","id":"a"}"#;
+        let tombstone: CatalogDeltaEntry = serde_json::from_str(line).unwrap();
+        let reviewer = r#"{"type":"delete","entity":"reviewer","id":"r"}"#;
+        let reviewer: CatalogDeltaEntry = serde_json::from_str(reviewer).unwrap();
+
+        let crate::Data::Cataloger(data) = &base.data else {
+            unreachable!()
+        };
+        let cataloger = data.cataloger.clone();
+        let mut delta = DeltaSubstrate {
+            meta: DeltaMeta {
+                meta: Meta {
+                    version: "2".to_owned(),
+                    ..base.meta.clone()
+                },
+                delta: DeltaOf {
+                    base_version: "1".to_owned(),
+                },
+            },
+            data: DeltaData::Cataloger(CatalogerDelta {
+                cataloger: cataloger.clone(),
+                entries: vec![tombstone.clone(), reviewer],
+            }),
+        };
+        assert_eq!(
+            base.apply_delta(&delta).unwrap_err(),
+            errors::DeltaError::UnsupportedEntity { entity: "reviewer" }
+        );
+        assert_eq!(product_ids(&base).len(), 1);
+
+        delta.data = DeltaData::Cataloger(CatalogerDelta {
+            cataloger,
+            entries: vec![tombstone.clone(), tombstone],
+        });
+        base.apply_delta(&delta).unwrap();
+        assert!(product_ids(&base).is_empty());
+        assert_eq!(base.meta.version, "2");
+    }
+}
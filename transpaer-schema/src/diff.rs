@@ -0,0 +1,494 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::EntityKind;
+
+/// A value that differs between two versions of an entity.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// JSON pointer to the value relative to the entity, empty for the whole entity.
+    pub path: String,
+
+    /// The old value, `None` if it was absent.
+    pub old: Option<serde_json::Value>,
+
+    /// The new value, `None` if it was removed.
+    pub new: Option<serde_json::Value>,
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |value: &Option<serde_json::Value>| match value {
+            Some(value) => value.to_string(),
+            None => "(absent)".to_owned(),
+        };
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.path,
+            show(&self.old),
+            show(&self.new)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// Changes of a single entity identified by its kind and `id`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EntityChange {
+    pub kind: ChangeKind,
+    pub entity: EntityKind,
+    pub id: String,
+
+    /// For added and removed entities a single change with the whole entity at the empty path,
+    /// otherwise one change per differing value.
+    pub fields: Vec<FieldChange>,
+}
+
+impl std::fmt::Display for EntityChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = match self.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Modified => '~',
+        };
+        write!(f, "{sign} {} {:?}", self.entity.as_str(), self.id)?;
+        if self.kind == ChangeKind::Modified {
+            for field in &self.fields {
+                write!(f, "\n    {field}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Differences between the data of two versions of a substrate.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DataDiff {
+    /// Changes of the header describing the provider, identified by its old `id`.
+    pub about: Option<EntityChange>,
+
+    /// Changes of the entities, ordered by kind and `id`.
+    pub entities: Vec<EntityChange>,
+}
+
+impl DataDiff {
+    pub fn is_empty(&self) -> bool {
+        self.about.is_none() && self.entities.is_empty()
+    }
+}
+
+impl std::fmt::Display for DataDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entity in self.about.iter().chain(&self.entities) {
+            writeln!(f, "{entity}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Differences between the metadata of two versions of a substrate.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MetaDiff {
+    pub old_version: String,
+    pub new_version: String,
+
+    /// How the new version compares to the old one, see `Meta::compare_version`.
+    pub version_ordering: VersionOrdering,
+
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionOrdering {
+    Older,
+    Same,
+    Newer,
+}
+
+impl From<std::cmp::Ordering> for VersionOrdering {
+    fn from(ordering: std::cmp::Ordering) -> Self {
+        match ordering {
+            std::cmp::Ordering::Less => Self::Older,
+            std::cmp::Ordering::Equal => Self::Same,
+            std::cmp::Ordering::Greater => Self::Newer,
+        }
+    }
+}
+
+/// Differences between two versions of a substrate.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SubstrateDiff {
+    pub meta: MetaDiff,
+    pub data: DataDiff,
+}
+
+impl SubstrateDiff {
+    /// Checks if the data is the same; metadata changes are not taken into account.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl std::fmt::Display for SubstrateDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let meta = &self.meta;
+        writeln!(
+            f,
+            "version {:?} -> {:?} ({:?})",
+            meta.old_version, meta.new_version, meta.version_ordering
+        )?;
+        for field in &meta.fields {
+            writeln!(f, "~ meta {field}")?;
+        }
+        write!(f, "{}", self.data)
+    }
+}
+
+fn to_value(value: &impl Serialize) -> serde_json::Value {
+    serde_json::to_value(value).expect("substrates always serialize to JSON")
+}
+
+/// Escapes a key for use in a JSON pointer.
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Collects the differences between two JSON values.
+///
+/// Objects are compared key by key, everything else including lists is compared as a whole.
+fn diff_values(
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    path: &str,
+    changes: &mut Vec<FieldChange>,
+) {
+    match (old, new) {
+        (serde_json::Value::Object(old), serde_json::Value::Object(new)) => {
+            let keys: std::collections::BTreeSet<_> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                let path = format!("{path}/{}", escape(key));
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff_values(old, new, &path, changes),
+                    (old, new) => changes.push(FieldChange {
+                        path,
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    }),
+                }
+            }
+        }
+        (old, new) if old != new => changes.push(FieldChange {
+            path: path.to_owned(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn diff_fields(old: &impl Serialize, new: &impl Serialize) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_values(&to_value(old), &to_value(new), "", &mut changes);
+    changes
+}
+
+fn diff_about(
+    old: &impl Serialize,
+    new: &impl Serialize,
+    entity: EntityKind,
+    id: &str,
+) -> Option<EntityChange> {
+    let fields = diff_fields(old, new);
+    (!fields.is_empty()).then(|| EntityChange {
+        kind: ChangeKind::Modified,
+        entity,
+        id: id.to_owned(),
+        fields,
+    })
+}
+
+/// Compares two lists of entities keyed by `id`.
+///
+/// If several entities share an `id` only the first one is taken into account.
+fn diff_entities<T: Serialize>(
+    old: &[T],
+    new: &[T],
+    id: impl Fn(&T) -> &str,
+    entity: EntityKind,
+    changes: &mut Vec<EntityChange>,
+) {
+    let mut entities = BTreeMap::<&str, (Option<&T>, Option<&T>)>::new();
+    for e in old {
+        entities.entry(id(e)).or_default().0.get_or_insert(e);
+    }
+    for e in new {
+        entities.entry(id(e)).or_default().1.get_or_insert(e);
+    }
+
+    for (id, pair) in entities {
+        let (kind, fields) = match pair {
+            (Some(old), Some(new)) => {
+                let mut fields = Vec::new();
+                diff_values(&to_value(old), &to_value(new), "", &mut fields);
+                if fields.is_empty() {
+                    continue;
+                }
+                (ChangeKind::Modified, fields)
+            }
+            (old, new) => {
+                let kind = if new.is_some() {
+                    ChangeKind::Added
+                } else {
+                    ChangeKind::Removed
+                };
+                let field = FieldChange {
+                    path: String::new(),
+                    old: old.map(to_value),
+                    new: new.map(to_value),
+                };
+                (kind, vec![field])
+            }
+        };
+        changes.push(EntityChange {
+            kind,
+            entity,
+            id: id.to_owned(),
+            fields,
+        });
+    }
+}
+
+impl crate::Meta {
+    pub fn diff(&self, new: &Self) -> MetaDiff {
+        MetaDiff {
+            old_version: self.version.clone(),
+            new_version: new.version.clone(),
+            version_ordering: new.compare_version(self).into(),
+            fields: diff_fields(self, new),
+        }
+    }
+}
+
+impl crate::CatalogerData {
+    pub fn diff(&self, new: &Self) -> DataDiff {
+        let mut entities = Vec::new();
+        let (old_producers, new_producers) = (&self.producers, &new.producers);
+        diff_entities(
+            old_producers,
+            new_producers,
+            |p| &p.id,
+            EntityKind::Producer,
+            &mut entities,
+        );
+        let (old_products, new_products) = (&self.products, &new.products);
+        diff_entities(
+            old_products,
+            new_products,
+            |p| &p.id,
+            EntityKind::Product,
+            &mut entities,
+        );
+        DataDiff {
+            about: diff_about(
+                &self.cataloger,
+                &new.cataloger,
+                EntityKind::Cataloger,
+                &self.cataloger.id,
+            ),
+            entities,
+        }
+    }
+}
+
+impl crate::ProducerData {
+    pub fn diff(&self, new: &Self) -> DataDiff {
+        let mut entities = Vec::new();
+        let (old_products, new_products) = (&self.products, &new.products);
+        diff_entities(
+            old_products,
+            new_products,
+            |p| &p.id,
+            EntityKind::Product,
+            &mut entities,
+        );
+        let (old_reviewers, new_reviewers) = (&self.reviewers, &new.reviewers);
+        diff_entities(
+            old_reviewers,
+            new_reviewers,
+            |r| &r.id,
+            EntityKind::Reviewer,
+            &mut entities,
+        );
+        DataDiff {
+            about: diff_about(
+                &self.producer,
+                &new.producer,
+                EntityKind::Producer,
+                &self.producer.id,
+            ),
+            entities,
+        }
+    }
+}
+
+impl crate::ReviewerData {
+    pub fn diff(&self, new: &Self) -> DataDiff {
+        let mut entities = Vec::new();
+        let (old_producers, new_producers) = (&self.producers, &new.producers);
+        diff_entities(
+            old_producers,
+            new_producers,
+            |p| &p.id,
+            EntityKind::Producer,
+            &mut entities,
+        );
+        let (old_products, new_products) = (&self.products, &new.products);
+        diff_entities(
+            old_products,
+            new_products,
+            |p| &p.id,
+            EntityKind::Product,
+            &mut entities,
+        );
+        DataDiff {
+            about: diff_about(
+                &self.reviewer,
+                &new.reviewer,
+                EntityKind::Reviewer,
+                &self.reviewer.id,
+            ),
+            entities,
+        }
+    }
+}
+
+impl crate::Data {
+    /// Compares data of the same provider variant.
+    ///
+    /// Returns `None` if the variants differ.
+    pub fn diff(&self, new: &Self) -> Option<DataDiff> {
+        match (self, new) {
+            (Self::Cataloger(old), Self::Cataloger(new)) => Some(old.diff(new)),
+            (Self::Producer(old), Self::Producer(new)) => Some(old.diff(new)),
+            (Self::Reviewer(old), Self::Reviewer(new)) => Some(old.diff(new)),
+            _ => None,
+        }
+    }
+}
+
+impl crate::Substrate {
+    /// Compares two versions of a substrate.
+    ///
+    /// Returns `None` if the provider variants differ.
+    pub fn diff(&self, new: &Self) -> Option<SubstrateDiff> {
+        Some(SubstrateDiff {
+            meta: self.meta.diff(&new.meta),
+            data: self.data.diff(&new.data)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn product(id: &str, names: &[&str]) -> crate::CatalogProduct {
+        crate::CatalogProduct {
+            availability: None,
+            categorisation: None,
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: None,
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: names.iter().map(|n| n.to_string()).collect(),
+            origins: None,
+            related: None,
+            shopping: None,
+        }
+    }
+
+    fn data(products: Vec<crate::CatalogProduct>) -> crate::CatalogerData {
+        crate::CatalogerData {
+            cataloger: crate::AboutCataloger {
+                description: None,
+                id: "cataloger".to_owned(),
+                name: "Cataloger".to_owned(),
+                variant: crate::CatalogVariant::Database,
+                website: "https://example.com".to_owned(),
+            },
+            producers: Vec::new(),
+            products,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = data(vec![product("kept", &["Kept"]), product("gone", &[])]);
+        let mut new = data(vec![product("new", &[]), product("kept", &["Renamed"])]);
+        new.products[1].description = Some("Description".to_owned());
+        new.cataloger.description = Some("About".to_owned());
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.about,
+            Some(EntityChange {
+                kind: ChangeKind::Modified,
+                entity: EntityKind::Cataloger,
+                id: "cataloger".to_owned(),
+                fields: vec![FieldChange {
+                    path: "/description".to_owned(),
+                    old: None,
+                    new: Some("About".into()),
+                }],
+            })
+        );
+        let entities: Vec<_> = diff
+            .entities
+            .iter()
+            .map(|e| (e.kind, e.id.as_str()))
+            .collect();
+        assert_eq!(
+            entities,
+            vec![
+                (ChangeKind::Removed, "gone"),
+                (ChangeKind::Modified, "kept"),
+                (ChangeKind::Added, "new"),
+            ]
+        );
+        assert_eq!(
+            diff.entities[1].to_string(),
+            "~ product \"kept\"\n    \
+             /description: (absent) -> \"Description\"\n    \
+             /names: [\"Kept\"] -> [\"Renamed\"]"
+        );
+        assert_eq!(
+            diff.entities[2].fields[0].new,
+            Some(to_value(&new.products[0]))
+        );
+
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_values_escapes_keys() {
+        let mut changes = Vec::new();
+        let old = serde_json::json!({ "a/b": { "c~d": 1 } });
+        let new = serde_json::json!({ "a/b": { "c~d": 2 } });
+        diff_values(&old, &new, "", &mut changes);
+        assert_eq!(changes[0].path, "/a~1b/c~0d");
+    }
+}
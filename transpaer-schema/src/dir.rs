@@ -0,0 +1,189 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    data::{CatalogEntry, ProducerEntry, ReviewEntry},
+    defs, errors,
+    read::{self, FileIter, FileIterVariant, ReadOptions},
+    Meta, ProviderVariant,
+};
+
+/// Options for `read_dir` and `read_paths`.
+#[derive(Debug, Clone, Default)]
+pub struct DirOptions {
+    /// Options for opening each substrate; substrates skipped by them are not yielded.
+    pub read: ReadOptions,
+
+    /// Yields only substrates of the given variants; all of them if `None`.
+    pub variants: Option<Vec<ProviderVariant>>,
+
+    /// Maximal depth of the walked directories, the root being at depth 0; unlimited if `None`.
+    pub max_depth: Option<usize>,
+}
+
+impl DirOptions {
+    fn accepts(&self, meta: &Meta) -> bool {
+        self.variants
+            .as_ref()
+            .is_none_or(|variants| variants.contains(&meta.variant))
+    }
+}
+
+/// An entry of any provider variant.
+#[derive(Clone, Debug)]
+pub enum AnyEntry {
+    Catalog(CatalogEntry),
+    Producer(ProducerEntry),
+    Review(ReviewEntry),
+}
+
+/// An entry together with the substrate it was read from.
+#[derive(Clone, Debug)]
+pub struct SourcedEntry {
+    pub path: Arc<Path>,
+    pub meta: Arc<Meta>,
+
+    /// `false` if the substrate is not valid at `ReadOptions::valid_at`.
+    pub is_valid: bool,
+
+    pub entry: AnyEntry,
+}
+
+/// A substrate opened by `SubstrateFiles`.
+pub struct SubstrateFile {
+    pub path: PathBuf,
+    pub file: FileIter,
+}
+
+type Paths = Box<dyn Iterator<Item = Result<PathBuf, errors::ReadError>>>;
+
+/// Opens substrates one after another, see `read_dir` and `read_paths`.
+///
+/// Errors of one substrate are yielded without stopping the iteration.
+pub struct SubstrateFiles {
+    paths: Paths,
+    options: DirOptions,
+}
+
+impl SubstrateFiles {
+    /// Yields the entries of all the substrates, tagged with their source.
+    pub fn entries(self) -> SubstrateEntries {
+        SubstrateEntries {
+            files: self,
+            current: None,
+        }
+    }
+}
+
+impl std::iter::Iterator for SubstrateFiles {
+    type Item = Result<SubstrateFile, errors::ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let path = match self.paths.next()? {
+                Ok(path) => path,
+                Err(err) => return Some(Err(err)),
+            };
+            match read::open_file(&path, &self.options.read) {
+                Ok(Some(file)) if self.options.accepts(&file.meta) => {
+                    return Some(Ok(SubstrateFile { path, file }))
+                }
+                Ok(_) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+struct CurrentFile {
+    path: Arc<Path>,
+    meta: Arc<Meta>,
+    is_valid: bool,
+    entries: FileIterVariant,
+}
+
+impl CurrentFile {
+    fn next(&mut self) -> Option<Result<SourcedEntry, errors::ReadError>> {
+        let entry = match &mut self.entries {
+            FileIterVariant::Catalog(iter) => iter.next()?.map(AnyEntry::Catalog),
+            FileIterVariant::Producer(iter) => iter.next()?.map(AnyEntry::Producer),
+            FileIterVariant::Review(iter) => iter.next()?.map(AnyEntry::Review),
+        };
+        Some(entry.map(|entry| SourcedEntry {
+            path: self.path.clone(),
+            meta: self.meta.clone(),
+            is_valid: self.is_valid,
+            entry,
+        }))
+    }
+}
+
+/// Entries of many substrates, see `SubstrateFiles::entries`.
+///
+/// Errors of one substrate are yielded without stopping the iteration.
+pub struct SubstrateEntries {
+    files: SubstrateFiles,
+    current: Option<CurrentFile>,
+}
+
+impl std::iter::Iterator for SubstrateEntries {
+    type Item = Result<SourcedEntry, errors::ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.current.as_mut().and_then(CurrentFile::next) {
+                return Some(entry);
+            }
+            self.current = match self.files.next()? {
+                Ok(SubstrateFile { path, file }) => Some(CurrentFile {
+                    path: path.into(),
+                    meta: Arc::new(file.meta),
+                    is_valid: file.is_valid,
+                    entries: file.entries,
+                }),
+                Err(err) => return Some(Err(err)),
+            };
+        }
+    }
+}
+
+/// Walks the directory and opens the files with a supported extension, in the order of their
+/// names.
+pub fn read_dir(root: &Path, options: DirOptions) -> SubstrateFiles {
+    let mut walker = walkdir::WalkDir::new(root).sort_by_file_name();
+    if let Some(max_depth) = options.max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+    let root = root.to_owned();
+    let paths = walker.into_iter().filter_map(move |entry| match entry {
+        Ok(entry) => (entry.file_type().is_file() && defs::get_extension(entry.path()).is_some())
+            .then(|| Ok(entry.into_path())),
+        Err(err) => {
+            let path = err.path().unwrap_or(&root).to_owned();
+            Some(Err(errors::ReadError::Io {
+                source: err.into(),
+                path,
+            }))
+        }
+    });
+    SubstrateFiles {
+        paths: Box::new(paths),
+        options,
+    }
+}
+
+/// Opens the given files, for example the ones matched by a glob.
+///
+/// Unlike `read_dir` it does not skip files with an unsupported extension, but yields errors for
+/// them.
+pub fn read_paths(
+    paths: impl IntoIterator<Item = PathBuf, IntoIter: 'static>,
+    options: DirOptions,
+) -> SubstrateFiles {
+    SubstrateFiles {
+        paths: Box::new(paths.into_iter().map(Ok)),
+        options,
+    }
+}
@@ -79,4 +79,156 @@ pub enum ReadError {
         source: SubstrateError,
         path: std::path::PathBuf,
     },
+
+    #[snafu(display(
+        "Violates the `{definition}` schema (in {path:?}): {}",
+        display_violations(violations)
+    ))]
+    Schema {
+        definition: String,
+        violations: Vec<SchemaViolation>,
+        path: std::path::PathBuf,
+    },
+}
+
+/// A value that does not conform to the bundled JSON schema.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value.
+    pub pointer: String,
+
+    /// Description of the violated constraint.
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.pointer, self.message)
+    }
+}
+
+fn display_violations(violations: &[SchemaViolation]) -> String {
+    violations
+        .iter()
+        .map(SchemaViolation::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[snafu(module(gtin))]
+pub enum GtinError {
+    #[snafu(display("Unsupported barcode length: {length}"))]
+    Length { length: usize },
+
+    #[snafu(display("Non-digit character in barcode: {character:?}"))]
+    NonDigit { character: char },
+
+    #[snafu(display("Wrong check digit: expected {expected}, found {found}"))]
+    CheckDigit { expected: u8, found: u8 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[snafu(module(vat))]
+pub enum VatError {
+    #[snafu(display("Missing country prefix"))]
+    MissingPrefix,
+
+    #[snafu(display("Unknown country prefix: {prefix:?}"))]
+    UnknownCountry { prefix: String },
+
+    #[snafu(display("Wrong format for {prefix}: expected {expected}"))]
+    Format { prefix: String, expected: String },
+
+    #[snafu(display("Wrong check digits for {prefix}"))]
+    Checksum { prefix: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[snafu(module(region))]
+pub enum RegionError {
+    #[snafu(display("Unknown ISO 3166-1 alpha-2 code: {code:?}"))]
+    Unknown { code: String },
+
+    #[snafu(display("Not an ISO 3166-1 alpha-2 code: {code:?} (did you mean {suggestion:?}?)"))]
+    Misspelled {
+        code: String,
+        suggestion: &'static str,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[snafu(module(patch))]
+pub enum PatchError {
+    #[snafu(display("A {entity} cannot be patched in this substrate"))]
+    UnsupportedEntity { entity: &'static str },
+
+    #[snafu(display("No {entity} with id {id:?}"))]
+    UnknownEntity { entity: &'static str, id: String },
+
+    #[snafu(display("Cannot apply {operation} at {path:?} in {entity} {id:?}"))]
+    Path {
+        operation: &'static str,
+        path: String,
+        entity: &'static str,
+        id: String,
+    },
+
+    #[snafu(display("Patched {entity} {id:?} is not valid: {source}"))]
+    Invalid {
+        source: serde_json::Error,
+        entity: &'static str,
+        id: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[snafu(module(delta))]
+pub enum DeltaError {
+    #[snafu(display(
+        "Delta is based on version {base_version:?} but the substrate has version {version:?}"
+    ))]
+    VersionMismatch {
+        base_version: String,
+        version: String,
+    },
+
+    #[snafu(display("Delta is for a different provider variant"))]
+    VariantMismatch,
+
+    #[snafu(display("A {entity} cannot be deleted in this substrate"))]
+    UnsupportedEntity { entity: &'static str },
+}
+
+#[cfg(feature = "archive")]
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[snafu(module(archive))]
+pub enum ArchiveError {
+    #[snafu(display("Unsupported archive format (in {archive:?})"))]
+    UnsupportedFormat { archive: std::path::PathBuf },
+
+    #[snafu(display("Failed to read archive (in {archive:?}): {source}"))]
+    Io {
+        source: std::io::Error,
+        archive: std::path::PathBuf,
+    },
+
+    #[snafu(display("Failed to read zip archive (in {archive:?}): {source}"))]
+    Zip {
+        source: zip::result::ZipError,
+        archive: std::path::PathBuf,
+    },
+
+    #[snafu(display("Failed to read member {member:?} (in archive {archive:?}): {source}"))]
+    Member {
+        source: ReadError,
+        archive: std::path::PathBuf,
+        member: std::path::PathBuf,
+    },
 }
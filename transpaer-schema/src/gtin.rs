@@ -0,0 +1,251 @@
+use std::collections::BTreeSet;
+
+use snafu::prelude::*;
+
+use crate::errors;
+
+/// Length of the canonical GTIN-14 form.
+const GTIN_LEN: usize = 14;
+
+/// Barcode formats from the GS1 GTIN family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GtinFormat {
+    Ean8,
+    UpcA,
+    Ean13,
+    Gtin14,
+}
+
+impl GtinFormat {
+    /// Guesses the format from the number of digits.
+    pub fn from_len(len: usize) -> Option<Self> {
+        match len {
+            8 => Some(Self::Ean8),
+            12 => Some(Self::UpcA),
+            13 => Some(Self::Ean13),
+            14 => Some(Self::Gtin14),
+            _ => None,
+        }
+    }
+
+    pub fn digits(&self) -> usize {
+        match self {
+            Self::Ean8 => 8,
+            Self::UpcA => 12,
+            Self::Ean13 => 13,
+            Self::Gtin14 => 14,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ean8 => "EAN-8",
+            Self::UpcA => "UPC-A",
+            Self::Ean13 => "EAN-13",
+            Self::Gtin14 => "GTIN-14",
+        }
+    }
+}
+
+/// Computes the GS1 check digit for the given digits (without the check digit).
+///
+/// Weights alternate between 3 and 1 starting from the rightmost digit.
+pub fn check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| u32::from(*d) * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// A GS1 trade item number validated and stored in the canonical GTIN-14 form.
+///
+/// EAN-8, UPC-A and EAN-13 codes are left-padded with zeros, so the same product compares equal
+/// regardless of which format it was written in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Gtin(String);
+
+impl Gtin {
+    /// Validates the length, digits and check digit of an EAN-8, UPC-A, EAN-13 or GTIN-14 code.
+    pub fn parse(code: &str) -> Result<Self, errors::GtinError> {
+        let mut digits = Vec::with_capacity(code.len());
+        for character in code.chars() {
+            let digit = character
+                .to_digit(10)
+                .context(errors::gtin::NonDigitSnafu { character })?;
+            digits.push(digit as u8);
+        }
+
+        // Only ASCII digits are left, so the number of digits is also the length of `code`.
+        let length = digits.len();
+        GtinFormat::from_len(length).context(errors::gtin::LengthSnafu { length })?;
+
+        let (found, payload) = digits.split_last().expect("length checked above");
+        let expected = check_digit(payload);
+        ensure!(
+            expected == *found,
+            errors::gtin::CheckDigitSnafu {
+                expected,
+                found: *found
+            }
+        );
+
+        Ok(Self(format!("{code:0>GTIN_LEN$}")))
+    }
+
+    /// Checks if the code is a valid EAN-8, UPC-A, EAN-13 or GTIN-14.
+    pub fn is_valid(code: &str) -> bool {
+        Self::parse(code).is_ok()
+    }
+
+    /// Returns the 14-digit canonical form.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the shortest format able to represent this code without losing digits.
+    pub fn shortest_format(&self) -> GtinFormat {
+        let significant = self.0.trim_start_matches('0').len();
+        [GtinFormat::Ean8, GtinFormat::UpcA, GtinFormat::Ean13]
+            .into_iter()
+            .find(|format| significant <= format.digits())
+            .unwrap_or(GtinFormat::Gtin14)
+    }
+
+    /// Returns the code written in the given format, if it fits.
+    pub fn to_format(&self, format: GtinFormat) -> Option<String> {
+        let (padding, code) = self.0.split_at(GTIN_LEN - format.digits());
+        padding.bytes().all(|b| b == b'0').then(|| code.to_owned())
+    }
+}
+
+impl std::str::FromStr for Gtin {
+    type Err = errors::GtinError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Self::parse(code)
+    }
+}
+
+impl std::fmt::Display for Gtin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl crate::ProductIds {
+    /// Iterates over all EAN and GTIN codes as written in the data.
+    pub fn codes(&self) -> impl Iterator<Item = &String> {
+        self.ean.iter().flatten().chain(self.gtin.iter().flatten())
+    }
+
+    /// Returns the canonical GTIN-14 forms of all valid EAN and GTIN codes.
+    pub fn gtins(&self) -> BTreeSet<Gtin> {
+        self.codes()
+            .filter_map(|code| Gtin::parse(code).ok())
+            .collect()
+    }
+
+    /// Returns the EAN and GTIN codes that fail validation together with the reason.
+    pub fn invalid_codes(&self) -> Vec<(&String, errors::GtinError)> {
+        self.codes()
+            .filter_map(|code| Gtin::parse(code).err().map(|err| (code, err)))
+            .collect()
+    }
+
+    /// Checks if both identifier sets contain the same trade item number in any format.
+    pub fn shares_gtin(&self, other: &Self) -> bool {
+        let gtins = self.gtins();
+        other.gtins().iter().any(|gtin| gtins.contains(gtin))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_digit() {
+        assert_eq!(check_digit(&[9, 6, 3, 8, 5, 0, 7]), 4);
+        assert_eq!(check_digit(&[0, 3, 6, 0, 0, 0, 2, 9, 1, 4, 5]), 2);
+        assert_eq!(check_digit(&[8, 7, 1, 8, 8, 1, 9, 3, 7, 2, 2, 7]), 1);
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        assert_eq!(Gtin::parse("96385074").unwrap().as_str(), "00000096385074");
+        assert_eq!(
+            Gtin::parse("036000291452").unwrap().as_str(),
+            "00036000291452"
+        );
+        assert_eq!(
+            Gtin::parse("8718819372271").unwrap().as_str(),
+            "08718819372271"
+        );
+        assert_eq!(
+            Gtin::parse("08718819372271").unwrap().as_str(),
+            "08718819372271"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Gtin::parse("12345"),
+            Err(errors::GtinError::Length { length: 5 })
+        );
+        assert_eq!(
+            Gtin::parse("871881937227x"),
+            Err(errors::GtinError::NonDigit { character: 'x' })
+        );
+        assert_eq!(
+            Gtin::parse("12345é"),
+            Err(errors::GtinError::NonDigit { character: 'é' })
+        );
+        assert_eq!(
+            Gtin::parse("١٢٣٤٥٦٧"),
+            Err(errors::GtinError::NonDigit { character: '١' })
+        );
+        assert_eq!(
+            Gtin::parse("8718819372272"),
+            Err(errors::GtinError::CheckDigit {
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_formats() {
+        let gtin = Gtin::parse("036000291452").unwrap();
+        assert_eq!(gtin.shortest_format(), GtinFormat::UpcA);
+        assert_eq!(
+            gtin.to_format(GtinFormat::Ean13),
+            Some("0036000291452".to_owned())
+        );
+        assert_eq!(gtin.to_format(GtinFormat::Ean8), None);
+    }
+
+    #[test]
+    fn test_shares_gtin() {
+        let ean = crate::ProductIds {
+            ean: Some(vec!["8718819372271".to_owned()]),
+            gtin: None,
+            wiki: None,
+        };
+        let gtin = crate::ProductIds {
+            ean: None,
+            gtin: Some(vec!["08718819372271".to_owned()]),
+            wiki: None,
+        };
+        let other = crate::ProductIds {
+            ean: Some(vec!["8718819372288".to_owned()]),
+            gtin: None,
+            wiki: None,
+        };
+        assert!(ean.shares_gtin(&gtin));
+        assert!(!ean.shares_gtin(&other));
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{gtin::Gtin, vat::VatNumber, Data, EntityKind};
+
+/// Kind of an identifier an entity can be looked up by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdKind {
+    /// The `id` of the entity in the substrate.
+    Id,
+
+    /// An entry of `ProductIds::ean`.
+    ///
+    /// Indexed together with `Gtin`, since both hold GS1 trade item numbers.
+    Ean,
+
+    /// An entry of `ProductIds::gtin`.
+    Gtin,
+
+    /// An entry of `ProductIds::wiki` or `ProducerIds::wiki`.
+    Wiki,
+
+    /// An entry of `ProducerIds::vat`.
+    Vat,
+
+    /// An entry of `ProducerIds::domains`.
+    Domain,
+}
+
+impl IdKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Ean => "ean",
+            Self::Gtin => "gtin",
+            Self::Wiki => "wiki",
+            Self::Vat => "vat",
+            Self::Domain => "domain",
+        }
+    }
+
+    /// Brings an identifier to the form used as a key in the index.
+    ///
+    /// Valid EAN and GTIN codes are stored as GTIN-14, valid VAT numbers without spaces and
+    /// punctuation, domains in the canonical form `normalize` gives them. Other identifiers are
+    /// kept as written.
+    fn key(&self, value: &str) -> (Self, String) {
+        match self {
+            Self::Ean | Self::Gtin => (
+                Self::Gtin,
+                Gtin::parse(value)
+                    .map(|gtin| gtin.to_string())
+                    .unwrap_or_else(|_| value.to_owned()),
+            ),
+            Self::Vat => (
+                Self::Vat,
+                VatNumber::parse(value)
+                    .map(|vat| vat.to_string())
+                    .unwrap_or_else(|_| value.to_owned()),
+            ),
+            Self::Domain => (Self::Domain, crate::normalize::canonical_domain(value)),
+            Self::Id | Self::Wiki => (*self, value.to_owned()),
+        }
+    }
+}
+
+/// An indexed entity borrowed from the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Entity<'a> {
+    CatalogProducer(&'a crate::CatalogProducer),
+    CatalogProduct(&'a crate::CatalogProduct),
+    Producer(&'a crate::AboutProducer),
+    ProducerProduct(&'a crate::ProducerProduct),
+    ProducerReviewer(&'a crate::ProducerReviewer),
+    ReviewProducer(&'a crate::ReviewProducer),
+    ReviewProduct(&'a crate::ReviewProduct),
+}
+
+impl<'a> Entity<'a> {
+    pub fn kind(&self) -> EntityKind {
+        match self {
+            Self::CatalogProducer(_) | Self::Producer(_) | Self::ReviewProducer(_) => {
+                EntityKind::Producer
+            }
+            Self::CatalogProduct(_) | Self::ProducerProduct(_) | Self::ReviewProduct(_) => {
+                EntityKind::Product
+            }
+            Self::ProducerReviewer(_) => EntityKind::Reviewer,
+        }
+    }
+
+    pub fn id(&self) -> &'a str {
+        match self {
+            Self::CatalogProducer(producer) => &producer.id,
+            Self::CatalogProduct(product) => &product.id,
+            Self::Producer(producer) => &producer.id,
+            Self::ProducerProduct(product) => &product.id,
+            Self::ProducerReviewer(reviewer) => &reviewer.id,
+            Self::ReviewProducer(producer) => &producer.id,
+            Self::ReviewProduct(product) => &product.id,
+        }
+    }
+
+    /// Lists all identifiers of the entity, including its `id`.
+    fn identifiers(&self) -> Vec<(IdKind, &'a str)> {
+        let mut identifiers = vec![(IdKind::Id, self.id())];
+        match self {
+            Self::CatalogProducer(producer) => push_producer_ids(&producer.ids, &mut identifiers),
+            Self::Producer(producer) => push_producer_ids(&producer.ids, &mut identifiers),
+            Self::ReviewProducer(producer) => push_producer_ids(&producer.ids, &mut identifiers),
+            Self::CatalogProduct(product) => push_product_ids(&product.ids, &mut identifiers),
+            Self::ProducerProduct(product) => push_product_ids(&product.ids, &mut identifiers),
+            Self::ReviewProduct(product) => push_product_ids(&product.ids, &mut identifiers),
+            Self::ProducerReviewer(_) => {}
+        }
+        identifiers
+    }
+}
+
+fn push_ids<'a>(kind: IdKind, ids: &'a Option<Vec<String>>, result: &mut Vec<(IdKind, &'a str)>) {
+    result.extend(ids.iter().flatten().map(|id| (kind, id.as_str())));
+}
+
+fn push_product_ids<'a>(ids: &'a crate::ProductIds, result: &mut Vec<(IdKind, &'a str)>) {
+    push_ids(IdKind::Ean, &ids.ean, result);
+    push_ids(IdKind::Gtin, &ids.gtin, result);
+    push_ids(IdKind::Wiki, &ids.wiki, result);
+}
+
+fn push_producer_ids<'a>(ids: &'a crate::ProducerIds, result: &mut Vec<(IdKind, &'a str)>) {
+    push_ids(IdKind::Vat, &ids.vat, result);
+    push_ids(IdKind::Domain, &ids.domains, result);
+    push_ids(IdKind::Wiki, &ids.wiki, result);
+}
+
+/// An identifier shared by several entities of the same kind.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Collision {
+    pub kind: IdKind,
+
+    /// The identifier in its normalized form.
+    pub value: String,
+
+    pub entity: EntityKind,
+
+    /// `id`s of the entities sharing the identifier, in the order they appear in the data.
+    pub ids: Vec<String>,
+}
+
+impl std::fmt::Display for Collision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:?} is shared by {}s {:?}",
+            self.kind.as_str(),
+            self.value,
+            self.entity.as_str(),
+            self.ids
+        )
+    }
+}
+
+/// Lookup of entities by any of their identifiers.
+///
+/// Identifiers are normalized the same way when building the index and when looking them up, so
+/// e.g. an EAN-13 code also finds products listing the same code as GTIN-14.
+#[derive(Clone, Debug)]
+pub struct Index<'a> {
+    entities: Vec<Entity<'a>>,
+    keys: HashMap<(IdKind, String), Vec<usize>>,
+    collisions: Vec<Collision>,
+}
+
+impl<'a> Index<'a> {
+    /// Indexes all producers, products and reviewers in the data.
+    pub fn new(data: &'a Data) -> Self {
+        let entities: Vec<Entity<'a>> = match data {
+            Data::Cataloger(data) => data
+                .producers
+                .iter()
+                .map(Entity::CatalogProducer)
+                .chain(data.products.iter().map(Entity::CatalogProduct))
+                .collect(),
+            Data::Producer(data) => std::iter::once(Entity::Producer(&data.producer))
+                .chain(data.products.iter().map(Entity::ProducerProduct))
+                .chain(data.reviewers.iter().map(Entity::ProducerReviewer))
+                .collect(),
+            Data::Reviewer(data) => data
+                .producers
+                .iter()
+                .map(Entity::ReviewProducer)
+                .chain(data.products.iter().map(Entity::ReviewProduct))
+                .collect(),
+        };
+
+        let mut keys: HashMap<(IdKind, String), Vec<usize>> = HashMap::new();
+        for (position, entity) in entities.iter().enumerate() {
+            for (kind, value) in entity.identifiers() {
+                let positions = keys.entry(kind.key(value)).or_default();
+                if !positions.contains(&position) {
+                    positions.push(position);
+                }
+            }
+        }
+
+        let mut collisions = Vec::new();
+        for ((kind, value), positions) in &keys {
+            let mut by_entity: HashMap<EntityKind, Vec<usize>> = HashMap::new();
+            for &position in positions {
+                by_entity
+                    .entry(entities[position].kind())
+                    .or_default()
+                    .push(position);
+            }
+            for (entity, positions) in by_entity {
+                if positions.len() > 1 {
+                    collisions.push(Collision {
+                        kind: *kind,
+                        value: value.clone(),
+                        entity,
+                        ids: positions
+                            .iter()
+                            .map(|&position| entities[position].id().to_owned())
+                            .collect(),
+                    });
+                }
+            }
+        }
+        collisions.sort_by(|a, b| (a.kind, &a.value, a.entity).cmp(&(b.kind, &b.value, b.entity)));
+
+        Self {
+            entities,
+            keys,
+            collisions,
+        }
+    }
+
+    /// Iterates over the entities with the given identifier, in the order they appear in the data.
+    pub fn get(&self, kind: IdKind, value: &str) -> impl Iterator<Item = Entity<'a>> + '_ {
+        self.keys
+            .get(&kind.key(value))
+            .into_iter()
+            .flatten()
+            .map(|&position| self.entities[position])
+    }
+
+    /// Returns the first entity of the given kind with the given identifier.
+    pub fn find(&self, entity: EntityKind, kind: IdKind, value: &str) -> Option<Entity<'a>> {
+        self.get(kind, value).find(|e| e.kind() == entity)
+    }
+
+    /// Returns the first product with the given identifier.
+    pub fn product(&self, kind: IdKind, value: &str) -> Option<Entity<'a>> {
+        self.find(EntityKind::Product, kind, value)
+    }
+
+    /// Returns the first producer with the given identifier.
+    pub fn producer(&self, kind: IdKind, value: &str) -> Option<Entity<'a>> {
+        self.find(EntityKind::Producer, kind, value)
+    }
+
+    /// Identifiers shared by several entities of the same kind, ordered by identifier.
+    pub fn collisions(&self) -> &[Collision] {
+        &self.collisions
+    }
+
+    /// Number of indexed entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+impl Data {
+    /// Builds an index of the entities by their identifiers.
+    pub fn index(&self) -> Index<'_> {
+        Index::new(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn product(id: &str, ean: &[&str], gtin: &[&str]) -> crate::CatalogProduct {
+        let codes = |codes: &[&str]| Some(codes.iter().map(|c| c.to_string()).collect());
+        crate::CatalogProduct {
+            availability: None,
+            categorisation: None,
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: codes(ean),
+                gtin: codes(gtin),
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: None,
+            related: None,
+            shopping: None,
+        }
+    }
+
+    fn producer(id: &str, vat: &str, domain: &str) -> crate::CatalogProducer {
+        crate::CatalogProducer {
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProducerIds {
+                domains: Some(vec![domain.to_owned()]),
+                vat: Some(vec![vat.to_owned()]),
+                wiki: Some(vec![id.to_owned()]),
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: None,
+            websites: Vec::new(),
+        }
+    }
+
+    fn data() -> Data {
+        Data::Cataloger(crate::CatalogerData {
+            cataloger: crate::AboutCataloger {
+                description: None,
+                id: "cataloger".to_owned(),
+                name: "Cataloger".to_owned(),
+                variant: crate::CatalogVariant::Database,
+                website: "https://example.com".to_owned(),
+            },
+            producers: vec![
+                producer("Q1", "DE 136 695 976", "Example.com"),
+                producer("Q2", "DE136695976", "example.org"),
+            ],
+            products: vec![
+                product("a", &["5901234123457"], &[]),
+                product("b", &[], &["05901234123457"]),
+                product("c", &["96385074"], &["00000096385074"]),
+                product("Q1", &[], &[]),
+            ],
+        })
+    }
+
+    #[test]
+    fn test_lookup() {
+        let data = data();
+        let index = data.index();
+        assert_eq!(index.len(), 6);
+
+        let ids = |kind, value| index.get(kind, value).map(|e| e.id()).collect::<Vec<_>>();
+        assert_eq!(ids(IdKind::Ean, "5901234123457"), ["a", "b"]);
+        assert_eq!(ids(IdKind::Gtin, "5901234123457"), ["a", "b"]);
+        assert_eq!(ids(IdKind::Ean, "96385074"), ["c"]);
+        assert_eq!(ids(IdKind::Domain, "EXAMPLE.com"), ["Q1"]);
+        assert_eq!(ids(IdKind::Domain, "https://www.Example.com/"), ["Q1"]);
+        assert_eq!(ids(IdKind::Id, "Q1"), ["Q1", "Q1"]);
+        assert!(ids(IdKind::Ean, "unknown").is_empty());
+
+        let product = index.product(IdKind::Id, "Q1").unwrap();
+        assert!(matches!(product, Entity::CatalogProduct(_)));
+        let producer = index.producer(IdKind::Wiki, "Q2").unwrap();
+        assert_eq!(producer.id(), "Q2");
+    }
+
+    #[test]
+    fn test_collisions() {
+        let data = data();
+        let collisions = data.index().collisions().to_vec();
+        assert_eq!(
+            collisions,
+            vec![
+                Collision {
+                    kind: IdKind::Gtin,
+                    value: "05901234123457".to_owned(),
+                    entity: EntityKind::Product,
+                    ids: vec!["a".to_owned(), "b".to_owned()],
+                },
+                Collision {
+                    kind: IdKind::Vat,
+                    value: "DE136695976".to_owned(),
+                    entity: EntityKind::Producer,
+                    ids: vec!["Q1".to_owned(), "Q2".to_owned()],
+                },
+            ]
+        );
+        assert_eq!(
+            collisions[1].to_string(),
+            r#"vat "DE136695976" is shared by producers ["Q1", "Q2"]"#
+        );
+    }
+}
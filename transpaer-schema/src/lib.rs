@@ -6,15 +6,45 @@
 )]
 mod models;
 
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "async")]
+pub mod async_io;
+pub mod collect;
 mod data;
 mod defs;
+pub mod delta;
+pub mod diff;
+#[cfg(feature = "dir")]
+pub mod dir;
 pub mod errors;
+pub mod gtin;
+pub mod index;
 mod merge;
+mod meta;
+mod normalize;
+#[cfg(feature = "parallel")]
+mod parallel;
+pub mod patch;
+pub mod query;
 pub mod read;
+pub mod region;
 mod save;
+#[cfg(feature = "json-schema")]
+pub mod schema;
+mod score;
 mod sort;
+pub mod stats;
+pub mod succession;
+pub mod validate;
+pub mod vat;
 
 pub use chrono;
 pub use data::*;
 pub use defs::*;
+pub use gtin::{Gtin, GtinFormat};
 pub use models::*;
+pub use normalize::NormalizationReport;
+pub use region::CountryCode;
+pub use validate::ValidationReport;
+pub use vat::VatNumber;
@@ -0,0 +1,240 @@
+//! Command-line tool for checking, converting and combining substrates.
+//!
+//! Exit codes:
+//! - `0` - success, nothing to report,
+//! - `1` - validation errors were found or the compared substrates differ,
+//! - `2` - a file could not be read or written, or the arguments are invalid.
+
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use snafu::prelude::*;
+
+use transpaer_schema::{errors, read, stats, validate};
+
+const EXIT_FINDINGS: u8 = 1;
+const EXIT_FAILURE: u8 = 2;
+
+#[derive(Debug, Snafu)]
+enum CliError {
+    #[snafu(transparent)]
+    Read { source: errors::ReadError },
+
+    #[snafu(transparent)]
+    Save { source: errors::SaveError },
+
+    #[snafu(display("Substrates have different provider variants (in {path:?})"))]
+    VariantMismatch { path: PathBuf },
+
+    #[snafu(display("Failed to serialize the output as JSON: {source}"))]
+    Json { source: serde_json::Error },
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Work with Transpaer substrate files")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check substrates and report problems.
+    Validate {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+
+        /// Fail on warnings too, not only on errors.
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Convert a substrate between YAML and JSON Lines based on the file extensions.
+    Convert { input: PathBuf, output: PathBuf },
+
+    /// Sort all lists in a substrate.
+    Sort {
+        input: PathBuf,
+
+        /// Where to write the sorted substrate; overwrites the input if not given.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Merge substrates of the same provider variant, merging entities with the same `id`.
+    ///
+    /// The metadata and the provider header are taken from the first input.
+    Merge {
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<PathBuf>,
+
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// Count the entities in substrates and how often their fields are filled.
+    Stats {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+
+    /// List entities added, removed or changed between two versions of a substrate.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+}
+
+/// Outcome of a successful command.
+enum Outcome {
+    Clean,
+    Findings,
+}
+
+fn print_json(value: &impl Serialize) -> Result<(), CliError> {
+    let json = serde_json::to_string_pretty(value).context(JsonSnafu)?;
+    println!("{json}");
+    Ok(())
+}
+
+fn validate(files: &[PathBuf], format: Format, strict: bool) -> Result<Outcome, CliError> {
+    let mut report = validate::ValidationReport::new();
+    for path in files {
+        report += read::read_substrate(path)?.validate().with_substrate(path);
+    }
+    match format {
+        Format::Text => println!("{report}"),
+        Format::Json => print_json(&report)?,
+    }
+    let failed = report.has_errors() || (strict && report.count(validate::Severity::Warning) > 0);
+    Ok(if failed {
+        Outcome::Findings
+    } else {
+        Outcome::Clean
+    })
+}
+
+fn sort(input: &Path, output: Option<&Path>) -> Result<Outcome, CliError> {
+    let mut substrate = read::read_substrate(input)?;
+    substrate.sort();
+    substrate.save(output.unwrap_or(input))?;
+    Ok(Outcome::Clean)
+}
+
+fn merge(inputs: &[PathBuf], output: &Path) -> Result<Outcome, CliError> {
+    let (first, rest) = inputs
+        .split_first()
+        .expect("clap requires at least two inputs");
+    let mut substrate = read::read_substrate(first)?;
+    for path in rest {
+        let other = read::read_substrate(path)?;
+        substrate.data = substrate
+            .data
+            .merge(&other.data)
+            .context(VariantMismatchSnafu { path })?;
+    }
+    substrate.save(output)?;
+    Ok(Outcome::Clean)
+}
+
+#[derive(Serialize)]
+struct FileStats<'a> {
+    path: &'a Path,
+
+    #[serde(flatten)]
+    stats: stats::Stats,
+}
+
+fn print_entity_stats(name: &str, stats: &stats::EntityStats) {
+    println!("  {}: {}", name, stats.count);
+    for field in stats.filled.keys() {
+        let rate = stats.fill_rate(field).unwrap_or(0.0);
+        println!("    {field}: {:.1}%", rate * 100.0);
+    }
+}
+
+fn stats(files: &[PathBuf], format: Format) -> Result<Outcome, CliError> {
+    let mut reports = Vec::with_capacity(files.len());
+    for path in files {
+        let stats = read::iter_file(path)?.stats()?;
+        reports.push(FileStats { path, stats });
+    }
+    match format {
+        Format::Text => {
+            for report in &reports {
+                println!("{}:", report.path.display());
+                print_entity_stats("producers", &report.stats.producers);
+                print_entity_stats("products", &report.stats.products);
+                print_entity_stats("reviewers", &report.stats.reviewers);
+            }
+        }
+        Format::Json => print_json(&reports)?,
+    }
+    Ok(Outcome::Clean)
+}
+
+fn diff(old_path: &Path, new_path: &Path, format: Format) -> Result<Outcome, CliError> {
+    let old = read::read_substrate(old_path)?;
+    let new = read::read_substrate(new_path)?;
+    let diff = old
+        .diff(&new)
+        .context(VariantMismatchSnafu { path: new_path })?;
+    match format {
+        Format::Text => print!("{diff}"),
+        Format::Json => print_json(&diff)?,
+    }
+    Ok(if diff.is_empty() {
+        Outcome::Clean
+    } else {
+        Outcome::Findings
+    })
+}
+
+fn run(args: Args) -> Result<Outcome, CliError> {
+    match args.command {
+        Command::Validate {
+            files,
+            format,
+            strict,
+        } => validate(&files, format, strict),
+        Command::Convert { input, output } => {
+            read::read_substrate(&input)?.save(&output)?;
+            Ok(Outcome::Clean)
+        }
+        Command::Sort { input, output } => sort(&input, output.as_deref()),
+        Command::Merge { inputs, output } => merge(&inputs, &output),
+        Command::Stats { files, format } => stats(&files, format),
+        Command::Diff { old, new, format } => diff(&old, &new, format),
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(Outcome::Clean) => ExitCode::SUCCESS,
+        Ok(Outcome::Findings) => ExitCode::from(EXIT_FINDINGS),
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::from(EXIT_FAILURE)
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use crate::collect::Entities;
+
 // TODO: should error if strings are not the same.
 fn merge_optional_strings(s1: &Option<String>, s2: &Option<String>) -> Option<String> {
     s1.as_ref().or(s2.as_ref()).cloned()
@@ -65,6 +67,60 @@ fn merge_region_lists(p1: &crate::RegionList, p2: &crate::RegionList) -> crate::
     crate::RegionList(regions)
 }
 
+/// Merges availability regions preferring `All` over a list and a list over `Unknown`.
+fn merge_regions(r1: &crate::Regions, r2: &crate::Regions) -> crate::Regions {
+    use crate::{RegionVariant, Regions};
+    match (r1, r2) {
+        (Regions::Variant(RegionVariant::All), _) | (_, Regions::Variant(RegionVariant::All)) => {
+            Regions::Variant(RegionVariant::All)
+        }
+        (Regions::List(l1), Regions::List(l2)) => Regions::List(merge_region_lists(l1, l2)),
+        (Regions::List(_), Regions::Variant(RegionVariant::Unknown)) => r1.clone(),
+        (Regions::Variant(RegionVariant::Unknown), _) => r2.clone(),
+    }
+}
+
+/// Merges barcode lists treating codes with the same canonical GTIN-14 form as equal.
+///
+/// Invalid codes cannot be canonicalised, so they are compared verbatim.
+fn merge_unique_code_slices(v1: &[String], v2: &[String]) -> Vec<String> {
+    let key = |code: &String| match crate::Gtin::parse(code) {
+        Ok(gtin) => gtin.to_string(),
+        Err(_) => code.clone(),
+    };
+    let mut result = Vec::new();
+    let mut keys = HashSet::new();
+    for code in v1.iter().chain(v2) {
+        if keys.insert(key(code)) {
+            result.push(code.clone());
+        }
+    }
+    result.sort();
+    result
+}
+
+fn merge_optional_unique_code_vectors(
+    v1: &Option<Vec<String>>,
+    v2: &Option<Vec<String>>,
+) -> Option<Vec<String>> {
+    match (v1, v2) {
+        (Some(v1), Some(v2)) => Some(merge_unique_code_slices(v1, v2)),
+        (Some(v1), None) => Some(v1.clone()),
+        (None, Some(v2)) => Some(v2.clone()),
+        (None, None) => None,
+    }
+}
+
+impl crate::ProductIds {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            ean: merge_optional_unique_code_vectors(&self.ean, &other.ean),
+            gtin: merge_optional_unique_code_vectors(&self.gtin, &other.gtin),
+            wiki: merge_optional_unique_string_vectors(&self.wiki, &other.wiki),
+        }
+    }
+}
+
 impl crate::ProducerIds {
     pub fn merge(&self, other: &Self) -> Self {
         Self {
@@ -130,6 +186,243 @@ impl crate::ReviewProducer {
     }
 }
 
+fn merge_optional_product_origins(
+    o1: &Option<crate::ProductOrigins>,
+    o2: &Option<crate::ProductOrigins>,
+) -> Option<crate::ProductOrigins> {
+    match (o1, o2) {
+        (Some(o1), Some(o2)) => Some(crate::ProductOrigins {
+            producer_ids: merge_unique_string_slices(&o1.producer_ids, &o2.producer_ids),
+            regions: match (&o1.regions, &o2.regions) {
+                (Some(r1), Some(r2)) => Some(merge_region_lists(r1, r2)),
+                (r1, r2) => r1.as_ref().or(r2.as_ref()).cloned(),
+            },
+        }),
+        (o1, o2) => o1.as_ref().or(o2.as_ref()).cloned(),
+    }
+}
+
+fn merge_optional_availabilities(
+    a1: &Option<crate::ProductAvailability>,
+    a2: &Option<crate::ProductAvailability>,
+) -> Option<crate::ProductAvailability> {
+    match (a1, a2) {
+        (Some(a1), Some(a2)) => Some(crate::ProductAvailability {
+            regions: merge_regions(&a1.regions, &a2.regions),
+        }),
+        (a1, a2) => a1.as_ref().or(a2.as_ref()).cloned(),
+    }
+}
+
+fn merge_categorisations(
+    c1: &crate::ProductCategorisation,
+    c2: &crate::ProductCategorisation,
+) -> crate::ProductCategorisation {
+    let mut categories = c1.categories.clone();
+    for category in &c2.categories {
+        if !categories.contains(category) {
+            categories.push(category.clone());
+        }
+    }
+    categories.sort_by(|a, b| a.0.cmp(&b.0));
+    crate::ProductCategorisation { categories }
+}
+
+fn merge_optional_categorisations(
+    c1: &Option<crate::ProductCategorisation>,
+    c2: &Option<crate::ProductCategorisation>,
+) -> Option<crate::ProductCategorisation> {
+    match (c1, c2) {
+        (Some(c1), Some(c2)) => Some(merge_categorisations(c1, c2)),
+        (c1, c2) => c1.as_ref().or(c2.as_ref()).cloned(),
+    }
+}
+
+fn merge_optional_related(
+    r1: &Option<crate::RelatedProducts>,
+    r2: &Option<crate::RelatedProducts>,
+) -> Option<crate::RelatedProducts> {
+    match (r1, r2) {
+        (Some(r1), Some(r2)) => Some(crate::RelatedProducts {
+            followed_by: merge_optional_unique_string_vectors(&r1.followed_by, &r2.followed_by),
+            preceded_by: merge_optional_unique_string_vectors(&r1.preceded_by, &r2.preceded_by),
+        }),
+        (r1, r2) => r1.as_ref().or(r2.as_ref()).cloned(),
+    }
+}
+
+fn merge_optional_shopping(
+    s1: &Option<crate::Shopping>,
+    s2: &Option<crate::Shopping>,
+) -> Option<crate::Shopping> {
+    match (s1, s2) {
+        (Some(s1), Some(s2)) => {
+            let mut entries = s1.0.clone();
+            for entry in &s2.0 {
+                if !entries
+                    .iter()
+                    .any(|e| e.shop == entry.shop && e.id == entry.id)
+                {
+                    entries.push(entry.clone());
+                }
+            }
+            Some(crate::Shopping(entries))
+        }
+        (s1, s2) => s1.as_ref().or(s2.as_ref()).cloned(),
+    }
+}
+
+impl crate::CatalogProduct {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            id: self.id.clone(),
+            ids: self.ids.merge(&other.ids),
+            names: merge_unique_string_slices(&self.names, &other.names),
+            description: merge_optional_strings(&self.description, &other.description),
+            images: merge_unique_string_slices(&self.images, &other.images),
+            categorisation: merge_optional_categorisations(
+                &self.categorisation,
+                &other.categorisation,
+            ),
+            origins: merge_optional_product_origins(&self.origins, &other.origins),
+            availability: merge_optional_availabilities(&self.availability, &other.availability),
+            related: merge_optional_related(&self.related, &other.related),
+            shopping: merge_optional_shopping(&self.shopping, &other.shopping),
+        }
+    }
+}
+
+impl crate::ProducerProduct {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            id: self.id.clone(),
+            ids: self.ids.merge(&other.ids),
+            names: merge_unique_string_slices(&self.names, &other.names),
+            // TODO: should error if descriptions are not the same.
+            description: if self.description.is_empty() {
+                other.description.clone()
+            } else {
+                self.description.clone()
+            },
+            images: merge_unique_string_slices(&self.images, &other.images),
+            categorisation: merge_categorisations(&self.categorisation, &other.categorisation),
+            origins: merge_optional_product_origins(&self.origins, &other.origins),
+            availability: merge_optional_availabilities(&self.availability, &other.availability),
+            related: merge_optional_related(&self.related, &other.related),
+            shopping: merge_optional_shopping(&self.shopping, &other.shopping),
+        }
+    }
+}
+
+impl crate::ReviewProduct {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            id: self.id.clone(),
+            ids: self.ids.merge(&other.ids),
+            names: merge_unique_string_slices(&self.names, &other.names),
+            summary: merge_optional_strings(&self.summary, &other.summary),
+            images: merge_unique_string_slices(&self.images, &other.images),
+            categorisation: merge_optional_categorisations(
+                &self.categorisation,
+                &other.categorisation,
+            ),
+            origins: merge_optional_product_origins(&self.origins, &other.origins),
+            availability: merge_optional_availabilities(&self.availability, &other.availability),
+            related: merge_optional_related(&self.related, &other.related),
+            shopping: merge_optional_shopping(&self.shopping, &other.shopping),
+            reports: merge_optional_reports(&self.reports, &other.reports),
+            review: merge_optional_reviews(&self.review, &other.review),
+        }
+    }
+}
+
+impl crate::ProducerReviewer {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            id: self.id.clone(),
+            names: merge_unique_string_slices(&self.names, &other.names),
+            description: merge_optional_strings(&self.description, &other.description),
+        }
+    }
+}
+
+impl crate::CatalogerData {
+    /// Merges the entities of both catalogs, keeping the `cataloger` of `self`.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            cataloger: self.cataloger.clone(),
+            producers: Entities::merge_by_id(
+                &self.producers,
+                &other.producers,
+                |p| &p.id,
+                crate::CatalogProducer::merge,
+            ),
+            products: Entities::merge_by_id(
+                &self.products,
+                &other.products,
+                |p| &p.id,
+                crate::CatalogProduct::merge,
+            ),
+        }
+    }
+}
+
+impl crate::ProducerData {
+    /// Merges the entities of both substrates, keeping the `producer` of `self`.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            producer: self.producer.clone(),
+            products: Entities::merge_by_id(
+                &self.products,
+                &other.products,
+                |p| &p.id,
+                crate::ProducerProduct::merge,
+            ),
+            reviewers: Entities::merge_by_id(
+                &self.reviewers,
+                &other.reviewers,
+                |r| &r.id,
+                crate::ProducerReviewer::merge,
+            ),
+        }
+    }
+}
+
+impl crate::ReviewerData {
+    /// Merges the entities of both substrates, keeping the `reviewer` of `self`.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            reviewer: self.reviewer.clone(),
+            producers: Entities::merge_by_id(
+                &self.producers,
+                &other.producers,
+                |p| &p.id,
+                crate::ReviewProducer::merge,
+            ),
+            products: Entities::merge_by_id(
+                &self.products,
+                &other.products,
+                |p| &p.id,
+                crate::ReviewProduct::merge,
+            ),
+        }
+    }
+}
+
+impl crate::data::Data {
+    /// Merges data of the same provider variant.
+    ///
+    /// Returns `None` if the variants differ.
+    pub fn merge(&self, other: &Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Cataloger(d1), Self::Cataloger(d2)) => Some(Self::Cataloger(d1.merge(d2))),
+            (Self::Producer(d1), Self::Producer(d2)) => Some(Self::Producer(d1.merge(d2))),
+            (Self::Reviewer(d1), Self::Reviewer(d2)) => Some(Self::Reviewer(d1.merge(d2))),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -177,4 +470,41 @@ mod test {
             Some(vec![a, b, c])
         );
     }
+
+    #[test]
+    fn test_merge_unique_code_slices() {
+        let ean = "8718819372271".to_string();
+        let gtin = "08718819372271".to_string();
+        let other = "8718819372288".to_string();
+        let invalid = "123".to_string();
+        assert_eq!(
+            merge_unique_code_slices(
+                &[ean.clone(), invalid.clone()],
+                &[gtin, other.clone(), invalid.clone()]
+            ),
+            vec![invalid, ean, other]
+        );
+    }
+
+    #[test]
+    fn test_merge_regions() {
+        use crate::{RegionList, RegionVariant, Regions};
+        let all = Regions::Variant(RegionVariant::All);
+        let unknown = Regions::Variant(RegionVariant::Unknown);
+        let list = |regions: &[&str]| {
+            Regions::List(RegionList(regions.iter().map(|r| r.to_string()).collect()))
+        };
+        assert_eq!(merge_regions(&all, &all), all);
+        assert_eq!(merge_regions(&all, &unknown), all);
+        assert_eq!(merge_regions(&unknown, &all), all);
+        assert_eq!(merge_regions(&all, &list(&["DE"])), all);
+        assert_eq!(merge_regions(&list(&["DE"]), &all), all);
+        assert_eq!(merge_regions(&unknown, &unknown), unknown);
+        assert_eq!(merge_regions(&unknown, &list(&["DE"])), list(&["DE"]));
+        assert_eq!(merge_regions(&list(&["DE"]), &unknown), list(&["DE"]));
+        assert_eq!(
+            merge_regions(&list(&["PL", "DE"]), &list(&["FR", "DE"])),
+            list(&["DE", "FR", "PL"])
+        );
+    }
 }
@@ -0,0 +1,81 @@
+impl crate::Meta {
+    /// Checks if the instant falls into the `valid_from..=valid_to` window.
+    ///
+    /// Missing bounds are treated as open.
+    pub fn is_valid_at(&self, instant: chrono::DateTime<chrono::Utc>) -> bool {
+        self.valid_from
+            .is_none_or(|valid_from| valid_from <= instant)
+            && self.valid_to.is_none_or(|valid_to| instant <= valid_to)
+    }
+
+    /// Compares the versions of two substrates.
+    ///
+    /// Versions are compared segment by segment, splitting on `.`. Numeric segments are compared
+    /// as numbers, all others as strings, so `1.10` is newer than `1.9`.
+    pub fn compare_version(&self, other: &Self) -> std::cmp::Ordering {
+        compare_versions(&self.version, &other.version)
+    }
+}
+
+fn compare_versions(v1: &str, v2: &str) -> std::cmp::Ordering {
+    let mut s1 = v1.split('.');
+    let mut s2 = v2.split('.');
+    loop {
+        let ordering = match (s1.next(), s2.next()) {
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => a.cmp(b),
+            },
+            (Some(_), None) => std::cmp::Ordering::Greater,
+            (None, Some(_)) => std::cmp::Ordering::Less,
+            (None, None) => return std::cmp::Ordering::Equal,
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn at(day: u32) -> chrono::DateTime<chrono::Utc> {
+        chrono::NaiveDate::from_ymd_opt(2025, 1, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn test_is_valid_at() {
+        let mut meta = crate::Meta {
+            authors: Vec::new(),
+            creation_timestamp: None,
+            description: None,
+            title: "Title".to_owned(),
+            valid_from: None,
+            valid_to: None,
+            variant: crate::ProviderVariant::Cataloger,
+            version: "1".to_owned(),
+        };
+        assert!(meta.is_valid_at(at(1)));
+
+        meta.valid_from = Some(at(2));
+        meta.valid_to = Some(at(4));
+        assert!(!meta.is_valid_at(at(1)));
+        assert!(meta.is_valid_at(at(2)));
+        assert!(meta.is_valid_at(at(4)));
+        assert!(!meta.is_valid_at(at(5)));
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        use std::cmp::Ordering;
+        assert_eq!(compare_versions("1.2.0", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2", "1.2.1"), Ordering::Less);
+        assert_eq!(compare_versions("2024-01", "2023-12"), Ordering::Greater);
+    }
+}
@@ -0,0 +1,593 @@
+use std::{collections::HashSet, hash::Hash};
+
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+
+/// Summary of the changes made by a `normalize` call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NormalizationReport {
+    /// Strings that were trimmed or converted to Unicode NFC.
+    pub strings: usize,
+
+    /// Domains that were lowercased or stripped of a scheme, `www.` prefix or trailing slash.
+    pub domains: usize,
+
+    /// EAN and GTIN codes stripped of non-digit characters.
+    pub codes: usize,
+
+    /// List entries and optional strings removed for being empty.
+    pub empty: usize,
+
+    /// List entries removed for being duplicates of an earlier entry.
+    pub duplicates: usize,
+}
+
+impl NormalizationReport {
+    /// Checks if nothing was changed.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::ops::AddAssign for NormalizationReport {
+    fn add_assign(&mut self, other: Self) {
+        self.strings += other.strings;
+        self.domains += other.domains;
+        self.codes += other.codes;
+        self.empty += other.empty;
+        self.duplicates += other.duplicates;
+    }
+}
+
+fn normalize_string(string: &mut String, report: &mut NormalizationReport) {
+    let normalized: String = string.trim().nfc().collect();
+    if normalized != *string {
+        *string = normalized;
+        report.strings += 1;
+    }
+}
+
+fn normalize_optional_string(string: &mut Option<String>, report: &mut NormalizationReport) {
+    if let Some(inner) = string {
+        normalize_string(inner, report);
+        if inner.is_empty() {
+            *string = None;
+            report.empty += 1;
+        }
+    }
+}
+
+/// Brings a domain to its canonical form: trimmed, in Unicode NFC and lower case, without a
+/// scheme, `www.` prefix or trailing slash.
+pub(crate) fn canonical_domain(domain: &str) -> String {
+    let mut normalized = domain.trim().nfc().collect::<String>().to_lowercase();
+    for prefix in ["https://", "http://"] {
+        if let Some(stripped) = normalized.strip_prefix(prefix) {
+            normalized = stripped.to_owned();
+        }
+    }
+    if let Some(stripped) = normalized.strip_prefix("www.") {
+        normalized = stripped.to_owned();
+    }
+    normalized.trim_end_matches('/').to_owned()
+}
+
+fn normalize_domain(domain: &mut String, report: &mut NormalizationReport) {
+    normalize_string(domain, report);
+    let normalized = canonical_domain(domain);
+    if normalized != *domain {
+        *domain = normalized;
+        report.domains += 1;
+    }
+}
+
+fn normalize_code(code: &mut String, report: &mut NormalizationReport) {
+    let normalized: String = code.chars().filter(char::is_ascii_digit).collect();
+    if normalized != *code {
+        *code = normalized;
+        report.codes += 1;
+    }
+}
+
+/// Removes repeated entries keeping the first occurrence of each, telling them apart by `key`.
+fn dedup_by_key<T, K: Eq + Hash>(
+    items: &mut Vec<T>,
+    report: &mut NormalizationReport,
+    key: impl Fn(&T) -> K,
+) {
+    let len = items.len();
+    let mut seen = HashSet::with_capacity(len);
+    items.retain(|item| seen.insert(key(item)));
+    report.duplicates += len - items.len();
+}
+
+/// Removes repeated entries keeping the first occurrence of each.
+fn dedup<T: Clone + Eq + Hash>(items: &mut Vec<T>, report: &mut NormalizationReport) {
+    dedup_by_key(items, report, T::clone);
+}
+
+fn normalize_list(
+    strings: &mut Vec<String>,
+    report: &mut NormalizationReport,
+    normalize: fn(&mut String, &mut NormalizationReport),
+) {
+    for string in strings.iter_mut() {
+        normalize(string, report);
+    }
+    let len = strings.len();
+    strings.retain(|s| !s.is_empty());
+    report.empty += len - strings.len();
+    dedup(strings, report);
+}
+
+fn normalize_strings(strings: &mut Vec<String>, report: &mut NormalizationReport) {
+    normalize_list(strings, report, normalize_string);
+}
+
+fn normalize_optional_list(
+    strings: &mut Option<Vec<String>>,
+    report: &mut NormalizationReport,
+    normalize: fn(&mut String, &mut NormalizationReport),
+) {
+    if let Some(strings) = strings {
+        normalize_list(strings, report, normalize);
+    }
+}
+
+impl crate::Meta {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_strings(&mut self.authors, &mut report);
+        normalize_optional_string(&mut self.description, &mut report);
+        normalize_string(&mut self.title, &mut report);
+        normalize_string(&mut self.version, &mut report);
+        report
+    }
+}
+
+impl crate::RegionList {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_strings(&mut self.0, &mut report);
+        report
+    }
+}
+
+impl crate::Regions {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        match self {
+            Self::Variant(_variant) => NormalizationReport::default(),
+            Self::List(list) => list.normalize(),
+        }
+    }
+}
+
+impl crate::ProductAvailability {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        self.regions.normalize()
+    }
+}
+
+impl crate::ProductCategorisation {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        for category in &mut self.categories {
+            normalize_string(&mut category.0, &mut report);
+        }
+        let len = self.categories.len();
+        self.categories.retain(|c| !c.0.is_empty());
+        report.empty += len - self.categories.len();
+        dedup(&mut self.categories, &mut report);
+        report
+    }
+}
+
+impl crate::ProductIds {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_optional_list(&mut self.ean, &mut report, normalize_code);
+        normalize_optional_list(&mut self.gtin, &mut report, normalize_code);
+        normalize_optional_list(&mut self.wiki, &mut report, normalize_string);
+        report
+    }
+}
+
+impl crate::ProducerIds {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_optional_list(&mut self.domains, &mut report, normalize_domain);
+        normalize_optional_list(&mut self.vat, &mut report, normalize_string);
+        normalize_optional_list(&mut self.wiki, &mut report, normalize_string);
+        report
+    }
+}
+
+impl crate::ProductOrigins {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_strings(&mut self.producer_ids, &mut report);
+        if let Some(regions) = &mut self.regions {
+            report += regions.normalize();
+        }
+        report
+    }
+}
+
+impl crate::ProducerOrigins {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        match &mut self.regions {
+            Some(regions) => regions.normalize(),
+            None => NormalizationReport::default(),
+        }
+    }
+}
+
+impl crate::RelatedProducts {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_optional_list(&mut self.followed_by, &mut report, normalize_string);
+        normalize_optional_list(&mut self.preceded_by, &mut report, normalize_string);
+        report
+    }
+}
+
+impl crate::Shopping {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        for entry in &mut self.0 {
+            normalize_string(&mut entry.id, &mut report);
+            normalize_string(&mut entry.description, &mut report);
+        }
+        dedup_by_key(&mut self.0, &mut report, |entry| {
+            (entry.shop, entry.id.clone(), entry.description.clone())
+        });
+        report
+    }
+}
+
+impl crate::Reports {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        for entry in &mut self.0 {
+            normalize_optional_string(&mut entry.title, &mut report);
+            normalize_optional_string(&mut entry.url, &mut report);
+        }
+        dedup_by_key(&mut self.0, &mut report, |entry| {
+            (entry.title.clone(), entry.url.clone())
+        });
+        report
+    }
+}
+
+impl crate::AboutCataloger {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_optional_string(&mut self.description, &mut report);
+        normalize_string(&mut self.id, &mut report);
+        normalize_string(&mut self.name, &mut report);
+        normalize_string(&mut self.website, &mut report);
+        report
+    }
+}
+
+impl crate::AboutProducer {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_optional_string(&mut self.description, &mut report);
+        normalize_string(&mut self.id, &mut report);
+        report += self.ids.normalize();
+        normalize_strings(&mut self.images, &mut report);
+        normalize_string(&mut self.name, &mut report);
+        if let Some(origins) = &mut self.origins {
+            report += origins.normalize();
+        }
+        normalize_strings(&mut self.websites, &mut report);
+        report
+    }
+}
+
+impl crate::AboutReviewer {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_string(&mut self.description, &mut report);
+        normalize_string(&mut self.id, &mut report);
+        normalize_string(&mut self.name, &mut report);
+        normalize_string(&mut self.website, &mut report);
+        report
+    }
+}
+
+impl crate::CatalogProduct {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        if let Some(availability) = &mut self.availability {
+            report += availability.normalize();
+        }
+        if let Some(categorisation) = &mut self.categorisation {
+            report += categorisation.normalize();
+        }
+        normalize_optional_string(&mut self.description, &mut report);
+        normalize_string(&mut self.id, &mut report);
+        report += self.ids.normalize();
+        normalize_strings(&mut self.images, &mut report);
+        normalize_strings(&mut self.names, &mut report);
+        if let Some(origins) = &mut self.origins {
+            report += origins.normalize();
+        }
+        if let Some(related) = &mut self.related {
+            report += related.normalize();
+        }
+        if let Some(shopping) = &mut self.shopping {
+            report += shopping.normalize();
+        }
+        report
+    }
+}
+
+impl crate::CatalogProducer {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_optional_string(&mut self.description, &mut report);
+        normalize_string(&mut self.id, &mut report);
+        report += self.ids.normalize();
+        normalize_strings(&mut self.images, &mut report);
+        normalize_strings(&mut self.names, &mut report);
+        if let Some(origins) = &mut self.origins {
+            report += origins.normalize();
+        }
+        normalize_strings(&mut self.websites, &mut report);
+        report
+    }
+}
+
+impl crate::ProducerProduct {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        if let Some(availability) = &mut self.availability {
+            report += availability.normalize();
+        }
+        report += self.categorisation.normalize();
+        normalize_string(&mut self.description, &mut report);
+        normalize_string(&mut self.id, &mut report);
+        report += self.ids.normalize();
+        normalize_strings(&mut self.images, &mut report);
+        normalize_strings(&mut self.names, &mut report);
+        if let Some(origins) = &mut self.origins {
+            report += origins.normalize();
+        }
+        if let Some(related) = &mut self.related {
+            report += related.normalize();
+        }
+        if let Some(shopping) = &mut self.shopping {
+            report += shopping.normalize();
+        }
+        report
+    }
+}
+
+impl crate::ProducerReviewer {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_optional_string(&mut self.description, &mut report);
+        normalize_string(&mut self.id, &mut report);
+        normalize_strings(&mut self.names, &mut report);
+        report
+    }
+}
+
+impl crate::ReviewProduct {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        if let Some(availability) = &mut self.availability {
+            report += availability.normalize();
+        }
+        if let Some(categorisation) = &mut self.categorisation {
+            report += categorisation.normalize();
+        }
+        normalize_string(&mut self.id, &mut report);
+        report += self.ids.normalize();
+        normalize_strings(&mut self.images, &mut report);
+        normalize_strings(&mut self.names, &mut report);
+        if let Some(origins) = &mut self.origins {
+            report += origins.normalize();
+        }
+        if let Some(related) = &mut self.related {
+            report += related.normalize();
+        }
+        if let Some(reports) = &mut self.reports {
+            report += reports.normalize();
+        }
+        if let Some(shopping) = &mut self.shopping {
+            report += shopping.normalize();
+        }
+        normalize_optional_string(&mut self.summary, &mut report);
+        report
+    }
+}
+
+impl crate::ReviewProducer {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        normalize_optional_string(&mut self.description, &mut report);
+        normalize_string(&mut self.id, &mut report);
+        report += self.ids.normalize();
+        normalize_strings(&mut self.images, &mut report);
+        normalize_strings(&mut self.names, &mut report);
+        if let Some(origins) = &mut self.origins {
+            report += origins.normalize();
+        }
+        if let Some(reports) = &mut self.reports {
+            report += reports.normalize();
+        }
+        normalize_strings(&mut self.websites, &mut report);
+        report
+    }
+}
+
+impl crate::CatalogerData {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = self.cataloger.normalize();
+        for producer in &mut self.producers {
+            report += producer.normalize();
+        }
+        for product in &mut self.products {
+            report += product.normalize();
+        }
+        report
+    }
+}
+
+impl crate::ProducerData {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = self.producer.normalize();
+        for product in &mut self.products {
+            report += product.normalize();
+        }
+        for reviewer in &mut self.reviewers {
+            report += reviewer.normalize();
+        }
+        report
+    }
+}
+
+impl crate::ReviewerData {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = self.reviewer.normalize();
+        for producer in &mut self.producers {
+            report += producer.normalize();
+        }
+        for product in &mut self.products {
+            report += product.normalize();
+        }
+        report
+    }
+}
+
+impl crate::data::Substrate {
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut report = self.meta.normalize();
+        report += match &mut self.data {
+            crate::data::Data::Cataloger(data) => data.normalize(),
+            crate::data::Data::Producer(data) => data.normalize(),
+            crate::data::Data::Reviewer(data) => data.normalize(),
+        };
+        report
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize_string() {
+        let mut report = NormalizationReport::default();
+
+        let mut string = "  Fairphone\t".to_string();
+        normalize_string(&mut string, &mut report);
+        assert_eq!(string, "Fairphone");
+
+        let mut string = "Cafe\u{301}".to_string();
+        normalize_string(&mut string, &mut report);
+        assert_eq!(string, "Caf\u{e9}");
+
+        let mut string = "Fairphone".to_string();
+        normalize_string(&mut string, &mut report);
+        assert_eq!(report.strings, 2);
+    }
+
+    #[test]
+    fn test_normalize_domain() {
+        let mut report = NormalizationReport::default();
+
+        let mut domain = "WWW.Example.COM/".to_string();
+        normalize_domain(&mut domain, &mut report);
+        assert_eq!(domain, "example.com");
+
+        let mut domain = " https://www.fairphone.com ".to_string();
+        normalize_domain(&mut domain, &mut report);
+        assert_eq!(domain, "fairphone.com");
+
+        let mut domain = "fairphone.com".to_string();
+        normalize_domain(&mut domain, &mut report);
+        assert_eq!(report.domains, 2);
+    }
+
+    #[test]
+    fn test_normalize_code() {
+        let mut report = NormalizationReport::default();
+        let mut code = "87 18819-372271".to_string();
+        normalize_code(&mut code, &mut report);
+        assert_eq!(code, "8718819372271");
+        assert_eq!(report.codes, 1);
+    }
+
+    #[test]
+    fn test_normalize_strings() {
+        let mut report = NormalizationReport::default();
+        let mut strings = vec![
+            " Caf\u{e9}".to_string(),
+            "".to_string(),
+            "Cafe\u{301}".to_string(),
+            "Bar".to_string(),
+        ];
+        normalize_strings(&mut strings, &mut report);
+        assert_eq!(strings, vec!["Caf\u{e9}".to_string(), "Bar".to_string()]);
+        assert_eq!(
+            report,
+            NormalizationReport {
+                strings: 2,
+                domains: 0,
+                codes: 0,
+                empty: 1,
+                duplicates: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_normalize_product_ids() {
+        let mut ids = crate::ProductIds {
+            ean: Some(vec![
+                "8718819372271".to_string(),
+                "8718-8193-72271".to_string(),
+            ]),
+            gtin: None,
+            wiki: Some(vec![" Q5019402".to_string()]),
+        };
+        let report = ids.normalize();
+        assert_eq!(ids.ean, Some(vec!["8718819372271".to_string()]));
+        assert_eq!(ids.wiki, Some(vec!["Q5019402".to_string()]));
+        assert_eq!(
+            report,
+            NormalizationReport {
+                strings: 1,
+                domains: 0,
+                codes: 1,
+                empty: 0,
+                duplicates: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut report = NormalizationReport::default();
+        let mut items = vec!["b", "a", "b", "c", "a", "b"];
+        dedup(&mut items, &mut report);
+        assert_eq!(items, ["b", "a", "c"]);
+        assert_eq!(report.duplicates, 3);
+
+        let report_entry = |title: &str| crate::Report {
+            title: Some(title.to_owned()),
+            url: None,
+        };
+        let mut reports = crate::Reports(vec![
+            report_entry("first"),
+            report_entry("second"),
+            report_entry("first"),
+        ]);
+        assert_eq!(reports.normalize().duplicates, 1);
+        assert_eq!(reports.0, [report_entry("first"), report_entry("second")]);
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::mpsc;
+
+use rayon::prelude::*;
+use serde::de::DeserializeOwned;
+use snafu::prelude::*;
+
+use crate::{
+    errors,
+    read::{parse_entry, EntryDefinitions, Lines, ParallelOptions},
+};
+
+type Entry<T> = Result<T, errors::ReadError>;
+
+enum Inner<T> {
+    /// Reads a batch of lines and parses it in parallel, keeping the order of the lines.
+    Ordered {
+        pool: rayon::ThreadPool,
+        lines: Lines,
+        batch_size: usize,
+        batch: std::vec::IntoIter<Entry<T>>,
+    },
+
+    /// Reads and parses batches ahead on a separate thread and receives them as they are done.
+    ///
+    /// Only that thread waits for the receiver, so the parsing threads never block on the channel.
+    Unordered {
+        receiver: mpsc::Receiver<Vec<Entry<T>>>,
+        batch: std::vec::IntoIter<Entry<T>>,
+    },
+}
+
+/// Entries parsed from JSON lines on multiple threads.
+///
+/// The lines are parsed on a thread pool of its own, so that consuming the entries on the global
+/// thread pool cannot starve the parsing.
+pub(crate) struct ParallelLines<T> {
+    path: std::path::PathBuf,
+    definitions: Option<EntryDefinitions>,
+    inner: Inner<T>,
+}
+
+fn parse_line<T: DeserializeOwned>(
+    line: std::io::Result<String>,
+    definitions: Option<EntryDefinitions>,
+    path: &std::path::Path,
+) -> Entry<T> {
+    let line = line.context(errors::read::IoSnafu { path })?;
+    parse_entry(&line, definitions, path)
+}
+
+fn parse_batch<T: DeserializeOwned + Send>(
+    lines: Vec<std::io::Result<String>>,
+    definitions: Option<EntryDefinitions>,
+    path: &std::path::Path,
+) -> Vec<Entry<T>> {
+    lines
+        .into_par_iter()
+        .map(|line| parse_line(line, definitions, path))
+        .collect()
+}
+
+impl<T: DeserializeOwned + Send + 'static> ParallelLines<T> {
+    pub(crate) fn from_lines(
+        lines: Lines,
+        path: std::path::PathBuf,
+        definitions: Option<EntryDefinitions>,
+        options: &ParallelOptions,
+    ) -> Self {
+        let batch_size = options.batch_size.max(1);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .thread_name(|index| format!("transpaer-parse-{index}"))
+            .build()
+            .expect("failed to spawn the parsing threads");
+        let inner = if options.ordered {
+            Inner::Ordered {
+                pool,
+                lines,
+                batch_size,
+                batch: Vec::new().into_iter(),
+            }
+        } else {
+            let (sender, receiver) = mpsc::sync_channel(1);
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let mut lines = lines;
+                loop {
+                    let batch: Vec<_> = lines.by_ref().take(batch_size).collect();
+                    if batch.is_empty() {
+                        break;
+                    }
+                    // Stops early once the receiver is dropped.
+                    let entries = pool.install(|| parse_batch(batch, definitions, &path));
+                    if sender.send(entries).is_err() {
+                        break;
+                    }
+                }
+            });
+            Inner::Unordered {
+                receiver,
+                batch: Vec::new().into_iter(),
+            }
+        };
+        Self {
+            path,
+            definitions,
+            inner,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Send> std::iter::Iterator for ParallelLines<T> {
+    type Item = Entry<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            Inner::Ordered {
+                pool,
+                lines,
+                batch_size,
+                batch,
+            } => {
+                if let Some(entry) = batch.next() {
+                    return Some(entry);
+                }
+                let lines: Vec<_> = lines.take(*batch_size).collect();
+                let (definitions, path) = (self.definitions, &self.path);
+                *batch = pool
+                    .install(|| parse_batch(lines, definitions, path))
+                    .into_iter();
+                batch.next()
+            }
+            Inner::Unordered { receiver, batch } => loop {
+                if let Some(entry) = batch.next() {
+                    return Some(entry);
+                }
+                *batch = receiver.recv().ok()?.into_iter();
+            },
+        }
+    }
+}
@@ -0,0 +1,605 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use snafu::prelude::*;
+
+use crate::{
+    delta::EntityList,
+    diff::{ChangeKind, DataDiff, EntityChange, SubstrateDiff},
+    errors, EntityKind,
+};
+
+/// A change to a single entity.
+///
+/// Paths are JSON pointers relative to the entity, the empty path refers to the whole entity.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    /// Sets the value, creating missing objects on the way.
+    ///
+    /// Setting the empty path of an entity that does not exist adds the entity.
+    Set { path: String, value: Value },
+
+    /// Removes the value if present.
+    Unset { path: String },
+
+    /// Adds the value to the list unless it is already there, creating the list if missing.
+    AddToList { path: String, value: Value },
+
+    /// Removes all occurrences of the value from the list.
+    RemoveFromList { path: String, value: Value },
+
+    /// Removes the whole entity.
+    Delete,
+}
+
+impl Operation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Set { .. } => "set",
+            Self::Unset { .. } => "unset",
+            Self::AddToList { .. } => "add_to_list",
+            Self::RemoveFromList { .. } => "remove_from_list",
+            Self::Delete => "delete",
+        }
+    }
+
+    fn path(&self) -> &str {
+        match self {
+            Self::Set { path, .. }
+            | Self::Unset { path }
+            | Self::AddToList { path, .. }
+            | Self::RemoveFromList { path, .. } => path,
+            Self::Delete => "",
+        }
+    }
+}
+
+/// An operation on the entity with the given kind and `id`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PatchEntry {
+    pub entity: EntityKind,
+
+    /// `id` of the entity, `None` for the substrate metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(flatten)]
+    pub operation: Operation,
+}
+
+/// A list of operations applied in order.
+///
+/// The provider header (e.g. the `cataloger` of a `CatalogerData`) is addressed by its kind and
+/// `id` like any other entity, so entries following a change of its `id` use the new one.
+///
+/// Added entities are appended at the end of their list, so the order of entities is not
+/// preserved.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Patch {
+    pub entries: Vec<PatchEntry>,
+}
+
+impl Patch {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn push_change(&mut self, change: &EntityChange) {
+        let entry = |operation| PatchEntry {
+            entity: change.entity,
+            id: (change.entity != EntityKind::Meta).then(|| change.id.clone()),
+            operation,
+        };
+        match change.kind {
+            ChangeKind::Removed => self.entries.push(entry(Operation::Delete)),
+            ChangeKind::Added | ChangeKind::Modified => {
+                for field in &change.fields {
+                    let path = field.path.clone();
+                    self.entries.push(entry(match &field.new {
+                        Some(value) => Operation::Set {
+                            path,
+                            value: value.clone(),
+                        },
+                        None => Operation::Unset { path },
+                    }));
+                }
+            }
+        }
+    }
+}
+
+impl From<&DataDiff> for Patch {
+    fn from(diff: &DataDiff) -> Self {
+        let mut patch = Self::default();
+        if let Some(about) = &diff.about {
+            // The header is addressed by its old `id` until the `id` itself is changed.
+            let mut about = about.clone();
+            about.fields.sort_by_key(|field| field.path == "/id");
+            patch.push_change(&about);
+        }
+        for change in &diff.entities {
+            patch.push_change(change);
+        }
+        patch
+    }
+}
+
+impl From<&SubstrateDiff> for Patch {
+    fn from(diff: &SubstrateDiff) -> Self {
+        let mut patch = Self::default();
+        patch.push_change(&EntityChange {
+            kind: ChangeKind::Modified,
+            entity: EntityKind::Meta,
+            id: String::new(),
+            fields: diff.meta.fields.clone(),
+        });
+        patch.entries.extend(Self::from(&diff.data).entries);
+        patch
+    }
+}
+
+/// Splits a JSON pointer into unescaped tokens.
+fn tokens(path: &str) -> Vec<String> {
+    path.split('/')
+        .skip(1)
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Returns the value at the path, creating missing objects on the way if `create` is set.
+fn lookup<'a>(mut value: &'a mut Value, tokens: &[String], create: bool) -> Option<&'a mut Value> {
+    for token in tokens {
+        if create && value.is_null() {
+            *value = Value::Object(serde_json::Map::new());
+        }
+        value = match value {
+            Value::Object(map) => {
+                if create {
+                    map.entry(token.as_str()).or_insert(Value::Null)
+                } else {
+                    map.get_mut(token.as_str())?
+                }
+            }
+            Value::Array(list) => list.get_mut(token.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Applies an operation other than `Delete` to a serialized entity.
+///
+/// Returns `None` if the path does not fit the entity.
+fn apply_operation(value: &mut Value, operation: &Operation) -> Option<()> {
+    match operation {
+        Operation::Set { path, value: new } => {
+            *lookup(value, &tokens(path), true)? = new.clone();
+        }
+        Operation::Unset { path } => {
+            let tokens = tokens(path);
+            let (last, parent) = tokens.split_last()?;
+            match lookup(value, parent, false) {
+                Some(Value::Object(map)) => {
+                    map.remove(last);
+                }
+                Some(Value::Array(list)) => {
+                    if let Some(index) = last.parse().ok().filter(|i| *i < list.len()) {
+                        list.remove(index);
+                    }
+                }
+                Some(_) => return None,
+                None => {}
+            }
+        }
+        Operation::AddToList { path, value: item } => {
+            let list = lookup(value, &tokens(path), true)?;
+            if list.is_null() {
+                *list = Value::Array(Vec::new());
+            }
+            let list = list.as_array_mut()?;
+            if !list.contains(item) {
+                list.push(item.clone());
+            }
+        }
+        Operation::RemoveFromList { path, value: item } => {
+            match lookup(value, &tokens(path), false) {
+                Some(Value::Array(list)) => list.retain(|v| v != item),
+                Some(_) => return None,
+                None => {}
+            }
+        }
+        Operation::Delete => return None,
+    }
+    Some(())
+}
+
+/// Applies an operation to a single entity through its JSON form.
+fn apply_to_entity<T: Serialize + DeserializeOwned>(
+    entity: &T,
+    entry: &PatchEntry,
+) -> Result<T, errors::PatchError> {
+    let kind = entry.entity.as_str();
+    let id = entry.id.clone().unwrap_or_default();
+    let mut value = serde_json::to_value(entity).expect("substrates always serialize to JSON");
+    let operation = &entry.operation;
+    apply_operation(&mut value, operation).context(errors::patch::PathSnafu {
+        operation: operation.as_str(),
+        path: operation.path(),
+        entity: kind,
+        id: &id,
+    })?;
+    serde_json::from_value(value).context(errors::patch::InvalidSnafu { entity: kind, id })
+}
+
+/// Applies an entry to the provider header.
+fn apply_to_about<T: Serialize + DeserializeOwned>(
+    about: &mut T,
+    about_id: &str,
+    entry: &PatchEntry,
+) -> Result<(), errors::PatchError> {
+    let entity = entry.entity.as_str();
+    ensure!(
+        entry.id.as_deref() == Some(about_id),
+        errors::patch::UnknownEntitySnafu {
+            entity,
+            id: entry.id.clone().unwrap_or_default(),
+        }
+    );
+    ensure!(
+        entry.operation != Operation::Delete,
+        errors::patch::UnsupportedEntitySnafu { entity }
+    );
+    *about = apply_to_entity(about, entry)?;
+    Ok(())
+}
+
+/// Applies an entry to the entity with the matching `id`.
+fn apply_to_entities<T: Serialize + DeserializeOwned, F: Fn(&T) -> &str>(
+    entities: &mut EntityList<T, F>,
+    entry: &PatchEntry,
+) -> Result<(), errors::PatchError> {
+    let entity = entry.entity.as_str();
+    let entry_id = entry.id.as_deref().unwrap_or_default();
+    match (&entry.operation, entities.get(entry_id)) {
+        (Operation::Delete, Some(_)) => entities.remove(entry_id),
+        (_, Some(old)) => {
+            let new = apply_to_entity(old, entry)?;
+            entities.replace(entry_id, new);
+        }
+        (Operation::Set { path, value }, None) if path.is_empty() => {
+            let new =
+                serde_json::from_value(value.clone()).context(errors::patch::InvalidSnafu {
+                    entity,
+                    id: entry_id,
+                })?;
+            entities.upsert(new);
+        }
+        (_, None) => {
+            return errors::patch::UnknownEntitySnafu {
+                entity,
+                id: entry_id,
+            }
+            .fail()
+        }
+    }
+    Ok(())
+}
+
+impl crate::CatalogerData {
+    /// Applies the patch leaving the data unchanged if any of the entries fails.
+    pub fn apply(&mut self, patch: &Patch) -> Result<(), errors::PatchError> {
+        let mut cataloger = self.cataloger.clone();
+        let mut producers = EntityList::new(self.producers.clone(), |p| &p.id);
+        let mut products = EntityList::new(self.products.clone(), |p| &p.id);
+        for entry in &patch.entries {
+            match entry.entity {
+                EntityKind::Cataloger => {
+                    let id = cataloger.id.clone();
+                    apply_to_about(&mut cataloger, &id, entry)?
+                }
+                EntityKind::Producer => apply_to_entities(&mut producers, entry)?,
+                EntityKind::Product => apply_to_entities(&mut products, entry)?,
+                entity => {
+                    let entity = entity.as_str();
+                    return errors::patch::UnsupportedEntitySnafu { entity }.fail();
+                }
+            }
+        }
+        self.cataloger = cataloger;
+        self.producers = producers.finish();
+        self.products = products.finish();
+        Ok(())
+    }
+}
+
+impl crate::ProducerData {
+    /// Applies the patch leaving the data unchanged if any of the entries fails.
+    pub fn apply(&mut self, patch: &Patch) -> Result<(), errors::PatchError> {
+        let mut producer = self.producer.clone();
+        let mut products = EntityList::new(self.products.clone(), |p| &p.id);
+        let mut reviewers = EntityList::new(self.reviewers.clone(), |r| &r.id);
+        for entry in &patch.entries {
+            match entry.entity {
+                EntityKind::Producer => {
+                    let id = producer.id.clone();
+                    apply_to_about(&mut producer, &id, entry)?
+                }
+                EntityKind::Product => apply_to_entities(&mut products, entry)?,
+                EntityKind::Reviewer => apply_to_entities(&mut reviewers, entry)?,
+                entity => {
+                    let entity = entity.as_str();
+                    return errors::patch::UnsupportedEntitySnafu { entity }.fail();
+                }
+            }
+        }
+        self.producer = producer;
+        self.products = products.finish();
+        self.reviewers = reviewers.finish();
+        Ok(())
+    }
+}
+
+impl crate::ReviewerData {
+    /// Applies the patch leaving the data unchanged if any of the entries fails.
+    pub fn apply(&mut self, patch: &Patch) -> Result<(), errors::PatchError> {
+        let mut reviewer = self.reviewer.clone();
+        let mut producers = EntityList::new(self.producers.clone(), |p| &p.id);
+        let mut products = EntityList::new(self.products.clone(), |p| &p.id);
+        for entry in &patch.entries {
+            match entry.entity {
+                EntityKind::Reviewer => {
+                    let id = reviewer.id.clone();
+                    apply_to_about(&mut reviewer, &id, entry)?
+                }
+                EntityKind::Producer => apply_to_entities(&mut producers, entry)?,
+                EntityKind::Product => apply_to_entities(&mut products, entry)?,
+                entity => {
+                    let entity = entity.as_str();
+                    return errors::patch::UnsupportedEntitySnafu { entity }.fail();
+                }
+            }
+        }
+        self.reviewer = reviewer;
+        self.producers = producers.finish();
+        self.products = products.finish();
+        Ok(())
+    }
+}
+
+impl crate::Data {
+    pub fn apply(&mut self, patch: &Patch) -> Result<(), errors::PatchError> {
+        match self {
+            Self::Cataloger(data) => data.apply(patch),
+            Self::Producer(data) => data.apply(patch),
+            Self::Reviewer(data) => data.apply(patch),
+        }
+    }
+}
+
+impl crate::Substrate {
+    /// Applies the patch to the metadata and the data.
+    ///
+    /// Leaves the substrate unchanged if any of the entries fails.
+    pub fn apply(&mut self, patch: &Patch) -> Result<(), errors::PatchError> {
+        let (meta_entries, data_entries): (Vec<_>, Vec<_>) = patch
+            .entries
+            .iter()
+            .cloned()
+            .partition(|entry| entry.entity == EntityKind::Meta);
+        let mut meta = self.meta.clone();
+        for entry in &meta_entries {
+            ensure!(
+                entry.operation != Operation::Delete,
+                errors::patch::UnsupportedEntitySnafu { entity: "meta" }
+            );
+            meta = apply_to_entity(&meta, entry)?;
+        }
+        self.data.apply(&Patch {
+            entries: data_entries,
+        })?;
+        self.meta = meta;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn product(id: &str, ean: &[&str]) -> crate::CatalogProduct {
+        crate::CatalogProduct {
+            availability: None,
+            categorisation: None,
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: Some(ean.iter().map(|e| e.to_string()).collect()),
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: None,
+            related: None,
+            shopping: None,
+        }
+    }
+
+    fn data(products: Vec<crate::CatalogProduct>) -> crate::CatalogerData {
+        crate::CatalogerData {
+            cataloger: crate::AboutCataloger {
+                description: None,
+                id: "cataloger".to_owned(),
+                name: "Cataloger".to_owned(),
+                variant: crate::CatalogVariant::Database,
+                website: "https://example.com".to_owned(),
+            },
+            producers: Vec::new(),
+            products,
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let patch: Patch = serde_yaml::from_str(indoc::indoc! {r#"
+            - entity: product
+              id: fixed
+              op: remove_from_list
+              path: /ids/ean
+              value: "1"
+            - entity: product
+              id: fixed
+              op: add_to_list
+              path: /ids/ean
+              value: "2"
+            - entity: product
+              id: fixed
+              op: set
+              path: /origins/producer_ids
+              value: ["producer"]
+            - entity: product
+              id: gone
+              op: delete
+            - entity: cataloger
+              id: cataloger
+              op: unset
+              path: /description
+        "#})
+        .unwrap();
+        let mut data = data(vec![product("fixed", &["1"]), product("gone", &[])]);
+        data.apply(&patch).unwrap();
+
+        let mut fixed = product("fixed", &["2"]);
+        fixed.origins = Some(crate::ProductOrigins {
+            producer_ids: vec!["producer".to_owned()],
+            regions: None,
+        });
+        assert_eq!(data.products, vec![fixed]);
+    }
+
+    #[test]
+    fn test_apply_renamed_and_readded() {
+        let patch: Patch = serde_yaml::from_str(indoc::indoc! {r#"
+            - entity: product
+              id: old
+              op: set
+              path: /id
+              value: new
+            - entity: product
+              id: new
+              op: add_to_list
+              path: /ids/ean
+              value: "2"
+            - entity: product
+              id: first
+              op: delete
+            - entity: product
+              id: first
+              op: set
+              path: ""
+              value: {id: first, ids: {ean: ["3"]}, images: [], names: []}
+        "#})
+        .unwrap();
+        let mut data = data(vec![product("first", &["1"]), product("old", &["1"])]);
+        data.apply(&patch).unwrap();
+        assert_eq!(
+            data.products,
+            vec![product("new", &["1", "2"]), product("first", &["3"])]
+        );
+    }
+
+    #[test]
+    fn test_apply_failure_leaves_data_unchanged() {
+        let original = data(vec![product("kept", &["1"])]);
+        let mut data = original.clone();
+        let patch = Patch {
+            entries: vec![
+                PatchEntry {
+                    entity: EntityKind::Product,
+                    id: Some("kept".to_owned()),
+                    operation: Operation::Delete,
+                },
+                PatchEntry {
+                    entity: EntityKind::Product,
+                    id: Some("missing".to_owned()),
+                    operation: Operation::Unset {
+                        path: "/names".to_owned(),
+                    },
+                },
+            ],
+        };
+        assert!(matches!(
+            data.apply(&patch),
+            Err(errors::PatchError::UnknownEntity { .. })
+        ));
+        assert_eq!(data, original);
+
+        let patch = Patch {
+            entries: vec![PatchEntry {
+                entity: EntityKind::Product,
+                id: Some("kept".to_owned()),
+                operation: Operation::Set {
+                    path: "/names".to_owned(),
+                    value: Value::Bool(true),
+                },
+            }],
+        };
+        assert!(matches!(
+            data.apply(&patch),
+            Err(errors::PatchError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_apply_diff() {
+        let meta = crate::Meta {
+            authors: Vec::new(),
+            creation_timestamp: None,
+            description: Some("Old".to_owned()),
+            title: "Title".to_owned(),
+            valid_from: None,
+            valid_to: None,
+            variant: crate::ProviderVariant::Cataloger,
+            version: "1.0".to_owned(),
+        };
+        let mut old = crate::Substrate {
+            meta: meta.clone(),
+            data: crate::Data::Cataloger(data(vec![
+                product("kept", &["1", "2"]),
+                product("gone", &[]),
+            ])),
+        };
+
+        let mut new_data = data(vec![product("kept", &["2"]), product("added", &["3"])]);
+        new_data.cataloger.description = Some("About".to_owned());
+        new_data.products[0].ids.wiki = Some(vec!["Q1".to_owned()]);
+        let new = crate::Substrate {
+            meta: crate::Meta {
+                description: None,
+                version: "1.1".to_owned(),
+                ..meta
+            },
+            data: crate::Data::Cataloger(new_data),
+        };
+
+        let patch = Patch::from(&old.diff(&new).unwrap());
+        old.apply(&patch).unwrap();
+        assert_eq!(old.meta, new.meta);
+        assert!(old.diff(&new).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_apply_diff_round_trip() {
+        let old = data(vec![product("kept", &["1"]), product("gone", &[])]);
+        let mut new = data(vec![product("kept", &["2"]), product("added", &["3"])]);
+        new.cataloger.id = "renamed".to_owned();
+        new.cataloger.name = "Renamed".to_owned();
+        new.cataloger.website = "https://example.org".to_owned();
+
+        let patch = Patch::from(&old.diff(&new));
+        let mut patched = old.clone();
+        patched.apply(&patch).unwrap();
+        assert_eq!(patched, new);
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{
+    data::{CatalogEntry, ProducerEntry, ReviewEntry},
+    errors,
+    read::{CatalogIter, ProducerIter, ReviewIter},
+    AboutReviewer, ProductAvailability, ProductCategory, Review,
+};
+
+/// Common view of the product types of all provider variants.
+pub trait Product {
+    fn availability(&self) -> Option<&ProductAvailability>;
+
+    fn categories(&self) -> &[ProductCategory];
+
+    fn producer_ids(&self) -> &[String];
+
+    /// The review of the product; only reviewers review products.
+    fn review(&self) -> Option<&Review> {
+        None
+    }
+}
+
+impl Product for crate::CatalogProduct {
+    fn availability(&self) -> Option<&ProductAvailability> {
+        self.availability.as_ref()
+    }
+
+    fn categories(&self) -> &[ProductCategory] {
+        self.categorisation
+            .as_ref()
+            .map_or(&[], |c| c.categories.as_slice())
+    }
+
+    fn producer_ids(&self) -> &[String] {
+        self.origins
+            .as_ref()
+            .map_or(&[], |o| o.producer_ids.as_slice())
+    }
+}
+
+impl Product for crate::ProducerProduct {
+    fn availability(&self) -> Option<&ProductAvailability> {
+        self.availability.as_ref()
+    }
+
+    fn categories(&self) -> &[ProductCategory] {
+        &self.categorisation.categories
+    }
+
+    fn producer_ids(&self) -> &[String] {
+        self.origins
+            .as_ref()
+            .map_or(&[], |o| o.producer_ids.as_slice())
+    }
+}
+
+impl Product for crate::ReviewProduct {
+    fn availability(&self) -> Option<&ProductAvailability> {
+        self.availability.as_ref()
+    }
+
+    fn categories(&self) -> &[ProductCategory] {
+        self.categorisation
+            .as_ref()
+            .map_or(&[], |c| c.categories.as_slice())
+    }
+
+    fn producer_ids(&self) -> &[String] {
+        self.origins
+            .as_ref()
+            .map_or(&[], |o| o.producer_ids.as_slice())
+    }
+
+    fn review(&self) -> Option<&Review> {
+        self.review.as_ref()
+    }
+}
+
+/// A condition on products, composable with `and`, `or` and `not`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    /// Matches every product.
+    Any,
+
+    /// Available in the region with the given alpha-2 code, see `Regions::includes`.
+    AvailableIn(String),
+
+    /// Has the given category.
+    InCategory(String),
+
+    /// Lists the producer with the given `id` in its origins.
+    FromProducer(String),
+
+    /// Has a review scoring at least the given value on the `[0, 1]` scale, see
+    /// `Review::normalized_score`.
+    MinScore(f64),
+
+    All(Vec<Filter>),
+    AnyOf(Vec<Filter>),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    pub fn available_in(region: impl Into<String>) -> Self {
+        Self::AvailableIn(region.into())
+    }
+
+    pub fn in_category(category: impl Into<String>) -> Self {
+        Self::InCategory(category.into())
+    }
+
+    pub fn from_producer(id: impl Into<String>) -> Self {
+        Self::FromProducer(id.into())
+    }
+
+    pub fn min_score(score: f64) -> Self {
+        Self::MinScore(score)
+    }
+
+    /// Matches products matching both filters.
+    pub fn and(self, other: Self) -> Self {
+        match self {
+            Self::Any => other,
+            Self::All(mut filters) => {
+                filters.push(other);
+                Self::All(filters)
+            }
+            filter => Self::All(vec![filter, other]),
+        }
+    }
+
+    /// Matches products matching any of the filters.
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            Self::AnyOf(mut filters) => {
+                filters.push(other);
+                Self::AnyOf(filters)
+            }
+            filter => Self::AnyOf(vec![filter, other]),
+        }
+    }
+
+    /// Matches products not matching the filter.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    /// Checks if the product matches.
+    ///
+    /// The `reviewer` is needed to compare review scores; without it `MinScore` never matches.
+    pub fn matches<P: Product + ?Sized>(
+        &self,
+        product: &P,
+        reviewer: Option<&AboutReviewer>,
+    ) -> bool {
+        match self {
+            Self::Any => true,
+            Self::AvailableIn(region) => product
+                .availability()
+                .is_some_and(|a| a.regions.includes(region)),
+            Self::InCategory(category) => product.categories().iter().any(|c| c.0 == *category),
+            Self::FromProducer(id) => product.producer_ids().contains(id),
+            Self::MinScore(score) => product
+                .review()
+                .zip(reviewer)
+                .and_then(|(review, reviewer)| review.normalized_score(reviewer))
+                .is_some_and(|s| s >= *score),
+            Self::All(filters) => filters.iter().all(|f| f.matches(product, reviewer)),
+            Self::AnyOf(filters) => filters.iter().any(|f| f.matches(product, reviewer)),
+            Self::Not(filter) => !filter.matches(product, reviewer),
+        }
+    }
+}
+
+impl crate::CatalogerData {
+    /// Iterates over the products matching the filter.
+    pub fn products_matching<'a>(
+        &'a self,
+        filter: &'a Filter,
+    ) -> impl Iterator<Item = &'a crate::CatalogProduct> + 'a {
+        self.products.iter().filter(|p| filter.matches(*p, None))
+    }
+}
+
+impl crate::ProducerData {
+    /// Iterates over the products matching the filter.
+    pub fn products_matching<'a>(
+        &'a self,
+        filter: &'a Filter,
+    ) -> impl Iterator<Item = &'a crate::ProducerProduct> + 'a {
+        self.products.iter().filter(|p| filter.matches(*p, None))
+    }
+}
+
+impl crate::ReviewerData {
+    /// Iterates over the products matching the filter.
+    pub fn products_matching<'a>(
+        &'a self,
+        filter: &'a Filter,
+    ) -> impl Iterator<Item = &'a crate::ReviewProduct> + 'a {
+        self.products
+            .iter()
+            .filter(|p| filter.matches(*p, Some(&self.reviewer)))
+    }
+}
+
+impl CatalogIter {
+    /// Reads the products matching the filter, skipping other entries.
+    ///
+    /// Read errors are passed through.
+    pub fn products_matching(
+        self,
+        filter: Filter,
+    ) -> impl Iterator<Item = Result<crate::CatalogProduct, errors::ReadError>> {
+        self.filter_map(move |entry| match entry {
+            Ok(CatalogEntry::Product(p)) => filter.matches(&p, None).then_some(Ok(p)),
+            Ok(CatalogEntry::Producer(_)) => None,
+            Err(err) => Some(Err(err)),
+        })
+    }
+}
+
+impl ProducerIter {
+    /// Reads the products matching the filter, skipping other entries.
+    ///
+    /// Read errors are passed through.
+    pub fn products_matching(
+        self,
+        filter: Filter,
+    ) -> impl Iterator<Item = Result<crate::ProducerProduct, errors::ReadError>> {
+        self.filter_map(move |entry| match entry {
+            Ok(ProducerEntry::Product(p)) => filter.matches(&p, None).then_some(Ok(p)),
+            Ok(ProducerEntry::Reviewer(_)) => None,
+            Err(err) => Some(Err(err)),
+        })
+    }
+}
+
+impl ReviewIter {
+    /// Reads the products matching the filter, skipping other entries.
+    ///
+    /// Review scores are compared using the scale of the reviewer from the header. Read errors are
+    /// passed through.
+    pub fn products_matching(
+        self,
+        filter: Filter,
+    ) -> impl Iterator<Item = Result<crate::ReviewProduct, errors::ReadError>> {
+        let reviewer = self.about().clone();
+        self.filter_map(move |entry| match entry {
+            Ok(ReviewEntry::Product(p)) => filter.matches(&p, Some(&reviewer)).then_some(Ok(p)),
+            Ok(ReviewEntry::Producer(_)) => None,
+            Err(err) => Some(Err(err)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn product(
+        id: &str,
+        regions: crate::Regions,
+        category: &str,
+        producer: &str,
+        score: i64,
+    ) -> crate::ReviewProduct {
+        crate::ReviewProduct {
+            availability: Some(ProductAvailability { regions }),
+            categorisation: Some(crate::ProductCategorisation {
+                categories: vec![ProductCategory(category.to_owned())],
+            }),
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: None,
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: Some(crate::ProductOrigins {
+                producer_ids: vec![producer.to_owned()],
+                regions: None,
+            }),
+            related: None,
+            reports: None,
+            review: Some(Review::ScoreReview(crate::ScoreReview { value: score })),
+            shopping: None,
+            summary: None,
+        }
+    }
+
+    fn data() -> crate::ReviewerData {
+        let list = |codes: &[&str]| {
+            crate::Regions::List(crate::RegionList(
+                codes.iter().map(|c| c.to_string()).collect(),
+            ))
+        };
+        crate::ReviewerData {
+            reviewer: AboutReviewer {
+                description: "Reviewer".to_owned(),
+                id: "reviewer".to_owned(),
+                name: "Reviewer".to_owned(),
+                reviews: Some(crate::AboutReview::ScoreReview(crate::AboutScoreReview {
+                    div: 1,
+                    max: 10,
+                    min: 0,
+                })),
+                website: "https://example.com".to_owned(),
+            },
+            producers: Vec::new(),
+            products: vec![
+                product("a", list(&["DE", "FR"]), "food", "p1", 8),
+                product(
+                    "b",
+                    crate::Regions::Variant(crate::RegionVariant::All),
+                    "food",
+                    "p2",
+                    3,
+                ),
+                product(
+                    "c",
+                    crate::Regions::Variant(crate::RegionVariant::Unknown),
+                    "toys",
+                    "p1",
+                    10,
+                ),
+            ],
+        }
+    }
+
+    fn ids(data: &crate::ReviewerData, filter: &Filter) -> Vec<String> {
+        data.products_matching(filter)
+            .map(|p| p.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_filters() {
+        let data = data();
+        assert_eq!(ids(&data, &Filter::available_in("DE")), ["a", "b"]);
+        assert_eq!(ids(&data, &Filter::available_in("PL")), ["b"]);
+        assert_eq!(ids(&data, &Filter::in_category("toys")), ["c"]);
+        assert_eq!(ids(&data, &Filter::from_producer("p1")), ["a", "c"]);
+        assert_eq!(ids(&data, &Filter::min_score(0.8)), ["a", "c"]);
+        assert!(!Filter::min_score(0.0).matches(&data.products[0], None));
+    }
+
+    #[test]
+    fn test_composition() {
+        let data = data();
+        let filter = Filter::Any
+            .and(Filter::in_category("food"))
+            .and(Filter::min_score(0.5).not());
+        assert_eq!(ids(&data, &filter), ["b"]);
+
+        let filter = Filter::from_producer("p2").or(Filter::in_category("toys"));
+        assert_eq!(ids(&data, &filter), ["b", "c"]);
+    }
+}
@@ -1,90 +1,443 @@
 use std::io::BufRead;
 
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use snafu::prelude::*;
 
 use crate::{
-    data::{CatalogEntry, ProducerEntry, ReviewEntry},
-    defs, errors,
+    collect::CollectOptions,
+    data::{CatalogEntry, ProducerEntry, ReviewEntry, Substrate},
+    defs,
+    delta::{CatalogerDelta, DeltaData, DeltaMeta, DeltaSubstrate, ProducerDelta, ReviewerDelta},
+    errors,
     models::{
         AboutCataloger, AboutProducer, AboutReviewer, CatalogerData, Meta, ProducerData,
         ProviderVariant, ReviewerData,
     },
 };
 
-type Lines = std::io::Lines<std::io::BufReader<std::fs::File>>;
+/// Lines of a JSON lines substrate, read from a file or from memory.
+pub(crate) type Lines = std::io::Lines<Box<dyn BufRead + Send>>;
+
+/// Schema definitions of the entries of one provider variant, keyed by the entry `type`.
+pub(crate) type EntryDefinitions = &'static [(&'static str, &'static str)];
+
+pub(crate) const CATALOG_DEFINITIONS: EntryDefinitions = &[
+    ("producer", "catalogProducer"),
+    ("product", "catalogProduct"),
+];
+pub(crate) const PRODUCER_DEFINITIONS: EntryDefinitions = &[
+    ("product", "producerProduct"),
+    ("reviewer", "producerReviewer"),
+];
+pub(crate) const REVIEW_DEFINITIONS: EntryDefinitions =
+    &[("producer", "reviewProducer"), ("product", "reviewProduct")];
+
+/// Schema definitions of a delta of one provider variant: the key and the definition of the
+/// provider header and the definitions of the upserts.
+type DeltaDefinitions = (&'static str, &'static str, EntryDefinitions);
+
+const CATALOG_DELTA_DEFINITIONS: DeltaDefinitions =
+    ("cataloger", "aboutCataloger", CATALOG_DEFINITIONS);
+const PRODUCER_DELTA_DEFINITIONS: DeltaDefinitions =
+    ("producer", "aboutProducer", PRODUCER_DEFINITIONS);
+const REVIEW_DELTA_DEFINITIONS: DeltaDefinitions =
+    ("reviewer", "aboutReviewer", REVIEW_DEFINITIONS);
+
+/// What to do with substrates that are not valid at `ReadOptions::valid_at`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidityPolicy {
+    /// Open the substrate and mark it with `FileIter::is_valid` set to `false`.
+    #[default]
+    Flag,
+
+    /// Do not open the substrate.
+    Skip,
+}
+
+/// How to parse JSON lines on multiple threads.
+#[cfg(feature = "parallel")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelOptions {
+    /// Number of lines read and parsed together when preserving the order.
+    pub batch_size: usize,
+
+    /// Yields the entries in the order of the lines.
+    ///
+    /// Without ordering the batches are read and parsed ahead on a separate thread while the
+    /// entries of the previous ones are consumed.
+    pub ordered: bool,
+}
+
+#[cfg(feature = "parallel")]
+impl Default for ParallelOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 1024,
+            ordered: true,
+        }
+    }
+}
+
+/// Options for `open_file`.
+///
+/// Built from the default with the setters, since fields are added along with features.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ReadOptions {
+    /// Instant at which the substrate should be valid; no check is done if `None`.
+    pub valid_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// What to do with substrates not valid at `valid_at`.
+    pub on_invalid: ValidityPolicy,
+
+    /// Checks every document or line against the bundled JSON schema before deserializing it.
+    #[cfg(feature = "json-schema")]
+    pub validate_schema: bool,
+
+    /// Parses JSON lines on the rayon thread pool if set.
+    #[cfg(feature = "parallel")]
+    pub parallel: Option<ParallelOptions>,
+}
+
+impl ReadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn valid_at(mut self, instant: chrono::DateTime<chrono::Utc>) -> Self {
+        self.valid_at = Some(instant);
+        self
+    }
+
+    pub fn on_invalid(mut self, policy: ValidityPolicy) -> Self {
+        self.on_invalid = policy;
+        self
+    }
+
+    #[cfg(feature = "json-schema")]
+    pub fn validate_schema(mut self, validate: bool) -> Self {
+        self.validate_schema = validate;
+        self
+    }
 
-fn build_yaml_content_iter(path: &std::path::Path) -> Result<FileIterVariant, errors::ReadError> {
+    #[cfg(feature = "parallel")]
+    pub fn parallel(mut self, options: ParallelOptions) -> Self {
+        self.parallel = Some(options);
+        self
+    }
+
+    /// Checks the validity window of the substrate.
+    ///
+    /// Returns `None` if the substrate should be skipped, otherwise whether it is valid.
+    pub(crate) fn check_validity(&self, meta: &Meta) -> Option<bool> {
+        let is_valid = self
+            .valid_at
+            .is_none_or(|instant| meta.is_valid_at(instant));
+        match (is_valid, self.on_invalid) {
+            (false, ValidityPolicy::Skip) => None,
+            (is_valid, _) => Some(is_valid),
+        }
+    }
+
+    /// Returns the schema definitions to check against, if schema validation is enabled.
+    #[cfg(feature = "json-schema")]
+    pub(crate) fn schema<T>(&self, definitions: T) -> Option<T> {
+        self.validate_schema.then_some(definitions)
+    }
+
+    /// Returns the schema definitions to check against, if schema validation is enabled.
+    #[cfg(not(feature = "json-schema"))]
+    pub(crate) fn schema<T>(&self, _definitions: T) -> Option<T> {
+        None
+    }
+}
+
+#[cfg(feature = "json-schema")]
+fn check_schema(
+    definition: &str,
+    value: &serde_json::Value,
+    path: &std::path::Path,
+) -> Result<(), errors::ReadError> {
+    let violations = crate::schema::validator().validate(definition, value);
+    ensure!(
+        violations.is_empty(),
+        errors::read::SchemaSnafu {
+            definition,
+            violations,
+            path
+        }
+    );
+    Ok(())
+}
+
+/// Deserializes a YAML document, checking it against the schema definition first if given.
+fn parse_yaml<T: DeserializeOwned>(
+    document: serde_yaml::Deserializer,
+    definition: Option<&str>,
+    path: &std::path::Path,
+) -> Result<T, errors::ReadError> {
+    #[cfg(feature = "json-schema")]
+    if let Some(definition) = definition {
+        let value: serde_yaml::Value =
+            serde::Deserialize::deserialize(document).context(errors::read::YamlSnafu { path })?;
+        let json = serde_json::to_value(&value).context(errors::read::JsonSnafu { path })?;
+        check_schema(definition, &json, path)?;
+        return T::deserialize(value).context(errors::read::YamlSnafu { path });
+    }
+    let _ = definition;
+    T::deserialize(document).context(errors::read::YamlSnafu { path })
+}
+
+/// Deserializes a JSON line, checking it against the schema definition first if given.
+pub(crate) fn parse_json<T: DeserializeOwned>(
+    line: &str,
+    definition: Option<&str>,
+    path: &std::path::Path,
+) -> Result<T, errors::ReadError> {
+    #[cfg(feature = "json-schema")]
+    if let Some(definition) = definition {
+        let value: serde_json::Value =
+            serde_json::from_str(line).context(errors::read::JsonSnafu { path })?;
+        check_schema(definition, &value, path)?;
+        return T::deserialize(value).context(errors::read::JsonSnafu { path });
+    }
+    let _ = definition;
+    serde_json::from_str(line).context(errors::read::JsonSnafu { path })
+}
+
+/// Deserializes a JSON line holding an entry, checking it against the schema definition of its
+/// `type` first if definitions are given.
+pub(crate) fn parse_entry<T: DeserializeOwned>(
+    line: &str,
+    definitions: Option<EntryDefinitions>,
+    path: &std::path::Path,
+) -> Result<T, errors::ReadError> {
+    #[cfg(feature = "json-schema")]
+    if let Some(definitions) = definitions {
+        let value: serde_json::Value =
+            serde_json::from_str(line).context(errors::read::JsonSnafu { path })?;
+        check_entry(&value, definitions, path)?;
+        return T::deserialize(value).context(errors::read::JsonSnafu { path });
+    }
+    let _ = definitions;
+    serde_json::from_str(line).context(errors::read::JsonSnafu { path })
+}
+
+/// Checks an entry against the schema definition of its `type`.
+#[cfg(feature = "json-schema")]
+fn check_entry(
+    value: &serde_json::Value,
+    definitions: EntryDefinitions,
+    path: &std::path::Path,
+) -> Result<(), errors::ReadError> {
+    let entry_type = value.get("type").and_then(serde_json::Value::as_str);
+    match definitions.iter().find(|(t, _)| Some(*t) == entry_type) {
+        Some((_, definition)) => check_schema(definition, value, path),
+        None => {
+            let types: Vec<_> = definitions.iter().map(|(t, _)| *t).collect();
+            let definitions: Vec<_> = definitions.iter().map(|(_, d)| *d).collect();
+            errors::read::SchemaSnafu {
+                definition: definitions.join(" or "),
+                violations: vec![errors::SchemaViolation {
+                    pointer: "/type".to_owned(),
+                    message: format!("expected one of {types:?}, found {entry_type:?}"),
+                }],
+                path,
+            }
+            .fail()
+        }
+    }
+}
+
+/// Checks an entry of a delta like `check_entry`, except for tombstones which are not part of
+/// the schema.
+#[cfg(feature = "json-schema")]
+fn check_delta_entry(
+    value: &serde_json::Value,
+    definitions: EntryDefinitions,
+    path: &std::path::Path,
+) -> Result<(), errors::ReadError> {
+    match value.get("type").and_then(serde_json::Value::as_str) {
+        Some("delete") => Ok(()),
+        _ => check_entry(value, definitions, path),
+    }
+}
+
+/// Deserializes a JSON line holding an entry of a delta, checking upserts against the schema
+/// definition of their `type` first if definitions are given.
+fn parse_delta_entry<T: DeserializeOwned>(
+    line: &str,
+    definitions: Option<EntryDefinitions>,
+    path: &std::path::Path,
+) -> Result<T, errors::ReadError> {
+    #[cfg(feature = "json-schema")]
+    if let Some(definitions) = definitions {
+        let value: serde_json::Value =
+            serde_json::from_str(line).context(errors::read::JsonSnafu { path })?;
+        check_delta_entry(&value, definitions, path)?;
+        return T::deserialize(value).context(errors::read::JsonSnafu { path });
+    }
+    let _ = definitions;
+    serde_json::from_str(line).context(errors::read::JsonSnafu { path })
+}
+
+/// Deserializes the data document of a YAML delta, checking the provider header and each upsert
+/// against the schema first if definitions are given.
+fn parse_yaml_delta<T: DeserializeOwned>(
+    document: serde_yaml::Deserializer,
+    definitions: Option<DeltaDefinitions>,
+    path: &std::path::Path,
+) -> Result<T, errors::ReadError> {
+    #[cfg(feature = "json-schema")]
+    if let Some((key, about, entries)) = definitions {
+        let value: serde_yaml::Value =
+            serde::Deserialize::deserialize(document).context(errors::read::YamlSnafu { path })?;
+        let json = serde_json::to_value(&value).context(errors::read::JsonSnafu { path })?;
+        check_schema(about, &json[key], path)?;
+        for entry in json["entries"].as_array().into_iter().flatten() {
+            check_delta_entry(entry, entries, path)?;
+        }
+        return T::deserialize(value).context(errors::read::YamlSnafu { path });
+    }
+    let _ = definitions;
+    T::deserialize(document).context(errors::read::YamlSnafu { path })
+}
+
+/// Returns the next YAML document, failing with `missing` if there is none.
+fn next_document<'de>(
+    deserializer: &mut serde_yaml::Deserializer<'de>,
+    missing: errors::SubstrateError,
+    path: &std::path::Path,
+) -> Result<serde_yaml::Deserializer<'de>, errors::ReadError> {
+    match deserializer.next() {
+        Some(document) => Ok(document),
+        None => Err(missing).context(errors::read::SubstrateSnafu { path }),
+    }
+}
+
+/// Returns the next line, failing with `missing` if there is none.
+fn next_line(
+    lines: &mut Lines,
+    missing: errors::SubstrateError,
+    path: &std::path::Path,
+) -> Result<String, errors::ReadError> {
+    match lines.next() {
+        Some(line) => line.context(errors::read::IoSnafu { path }),
+        None => Err(missing).context(errors::read::SubstrateSnafu { path }),
+    }
+}
+
+fn build_yaml_content_iter(
+    path: &std::path::Path,
+    options: &ReadOptions,
+) -> Result<Option<FileIter>, errors::ReadError> {
     let contents = std::fs::read_to_string(path).context(errors::read::IoSnafu { path })?;
-    let mut deserializer = serde_yaml::Deserializer::from_str(&contents);
+    parse_yaml_content(&contents, path, options)
+}
 
-    let meta: Meta = if let Some(header) = deserializer.next() {
-        Meta::deserialize(header).context(errors::read::YamlSnafu { path })?
-    } else {
-        return Err(errors::SubstrateError::NoMeta).context(errors::read::SubstrateSnafu { path });
-    };
+/// Parses the contents of a YAML substrate read from `path`.
+pub(crate) fn parse_yaml_content(
+    contents: &str,
+    path: &std::path::Path,
+    options: &ReadOptions,
+) -> Result<Option<FileIter>, errors::ReadError> {
+    let mut deserializer = serde_yaml::Deserializer::from_str(contents);
 
-    let data = if let Some(data) = deserializer.next() {
-        data
-    } else {
-        return Err(errors::SubstrateError::NoData).context(errors::read::SubstrateSnafu { path });
+    let header = next_document(&mut deserializer, errors::SubstrateError::NoMeta, path)?;
+    let meta: Meta = parse_yaml(header, options.schema("meta"), path)?;
+    let Some(is_valid) = options.check_validity(&meta) else {
+        return Ok(None);
     };
 
-    match meta.variant {
+    let data = next_document(&mut deserializer, errors::SubstrateError::NoData, path)?;
+
+    let entries = match meta.variant {
         ProviderVariant::Cataloger => {
-            let data =
-                CatalogerData::deserialize(data).context(errors::read::YamlSnafu { path })?;
-            Ok(FileIterVariant::Catalog(CatalogIter::from_data(data)))
+            let data: CatalogerData = parse_yaml(data, options.schema("catalogerData"), path)?;
+            FileIterVariant::Catalog(CatalogIter::from_data(data))
         }
         ProviderVariant::Producer => {
-            let data = ProducerData::deserialize(data).context(errors::read::YamlSnafu { path })?;
-            Ok(FileIterVariant::Producer(ProducerIter::from_data(data)))
+            let data: ProducerData = parse_yaml(data, options.schema("producerData"), path)?;
+            FileIterVariant::Producer(ProducerIter::from_data(data))
         }
         ProviderVariant::Reviewer => {
-            let data = ReviewerData::deserialize(data).context(errors::read::YamlSnafu { path })?;
-            Ok(FileIterVariant::Review(ReviewIter::from_data(data)))
+            let data: ReviewerData = parse_yaml(data, options.schema("reviewerData"), path)?;
+            FileIterVariant::Review(ReviewIter::from_data(data))
         }
-    }
+    };
+    Ok(Some(FileIter {
+        meta,
+        is_valid,
+        entries,
+    }))
 }
 
-fn build_lines_iter(path: &std::path::Path) -> Result<FileIterVariant, errors::ReadError> {
+fn open_lines(path: &std::path::Path) -> Result<Lines, errors::ReadError> {
     let file = std::fs::File::open(path).context(errors::read::IoSnafu { path })?;
-    let mut lines = std::io::BufReader::new(file).lines();
-
-    if let Some(meta_str) = lines.next() {
-        let meta_str = meta_str.context(errors::read::IoSnafu { path })?;
-        let meta: Meta =
-            serde_json::from_str(&meta_str).context(errors::read::JsonSnafu { path })?;
-        if let Some(about_str) = lines.next() {
-            let about_str = about_str.context(errors::read::IoSnafu { path })?;
-            Ok(match meta.variant {
-                ProviderVariant::Cataloger => {
-                    let _about: AboutCataloger = serde_json::from_str(&about_str)
-                        .context(errors::read::JsonSnafu { path })?;
-                    FileIterVariant::Catalog(CatalogIter::from_lines(lines, path.to_owned()))
-                }
-                ProviderVariant::Producer => {
-                    let _about: AboutProducer = serde_json::from_str(&about_str)
-                        .context(errors::read::JsonSnafu { path })?;
-                    FileIterVariant::Producer(ProducerIter::from_lines(lines, path.to_owned()))
-                }
-                ProviderVariant::Reviewer => {
-                    let _about: AboutReviewer = serde_json::from_str(&about_str)
-                        .context(errors::read::JsonSnafu { path })?;
-                    FileIterVariant::Review(ReviewIter::from_lines(lines, path.to_owned()))
-                }
-            })
-        } else {
-            Err(errors::SubstrateError::NoAbout).context(errors::read::SubstrateSnafu { path })
+    let reader: Box<dyn BufRead + Send> = Box::new(std::io::BufReader::new(file));
+    Ok(reader.lines())
+}
+
+fn build_lines_iter(
+    path: &std::path::Path,
+    options: &ReadOptions,
+) -> Result<Option<FileIter>, errors::ReadError> {
+    parse_lines(open_lines(path)?, path, options)
+}
+
+/// Parses the lines of a JSON lines substrate read from `path`.
+pub(crate) fn parse_lines(
+    mut lines: Lines,
+    path: &std::path::Path,
+    options: &ReadOptions,
+) -> Result<Option<FileIter>, errors::ReadError> {
+    let meta_str = next_line(&mut lines, errors::SubstrateError::NoMeta, path)?;
+    let meta: Meta = parse_json(&meta_str, options.schema("meta"), path)?;
+    let Some(is_valid) = options.check_validity(&meta) else {
+        return Ok(None);
+    };
+    let about_str = next_line(&mut lines, errors::SubstrateError::NoAbout, path)?;
+    let entries = match meta.variant {
+        ProviderVariant::Cataloger => {
+            let about: AboutCataloger =
+                parse_json(&about_str, options.schema("aboutCataloger"), path)?;
+            FileIterVariant::Catalog(CatalogIter::from_lines(
+                about,
+                lines,
+                path.to_owned(),
+                options,
+            ))
         }
-    } else {
-        Err(errors::SubstrateError::NoMeta).context(errors::read::SubstrateSnafu { path })
-    }
+        ProviderVariant::Producer => {
+            let about: AboutProducer =
+                parse_json(&about_str, options.schema("aboutProducer"), path)?;
+            FileIterVariant::Producer(ProducerIter::from_lines(
+                about,
+                lines,
+                path.to_owned(),
+                options,
+            ))
+        }
+        ProviderVariant::Reviewer => {
+            let about: AboutReviewer =
+                parse_json(&about_str, options.schema("aboutReviewer"), path)?;
+            FileIterVariant::Review(ReviewIter::from_lines(
+                about,
+                lines,
+                path.to_owned(),
+                options,
+            ))
+        }
+    };
+    Ok(Some(FileIter {
+        meta,
+        is_valid,
+        entries,
+    }))
 }
 
 struct ContentCatalogIter {
-    content: Vec<CatalogEntry>,
-    index: usize,
+    content: std::vec::IntoIter<CatalogEntry>,
 }
 
 impl ContentCatalogIter {
@@ -96,7 +449,9 @@ impl ContentCatalogIter {
         for producer in data.producers {
             content.push(CatalogEntry::Producer(producer))
         }
-        Self { content, index: 0 }
+        Self {
+            content: content.into_iter(),
+        }
     }
 }
 
@@ -104,19 +459,31 @@ impl std::iter::Iterator for ContentCatalogIter {
     type Item = Result<CatalogEntry, errors::ReadError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.index += 1;
-        self.content.get(self.index - 1).map(|e| Ok(e.clone()))
+        self.content.next().map(Ok)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.content.size_hint()
     }
 }
 
 struct LazyCatalogIter {
     path: std::path::PathBuf,
     lines: Lines,
+    definitions: Option<EntryDefinitions>,
 }
 
 impl LazyCatalogIter {
-    fn from_lines(lines: Lines, path: std::path::PathBuf) -> Self {
-        Self { lines, path }
+    fn from_lines(
+        lines: Lines,
+        path: std::path::PathBuf,
+        definitions: Option<EntryDefinitions>,
+    ) -> Self {
+        Self {
+            lines,
+            path,
+            definitions,
+        }
     }
 }
 
@@ -126,9 +493,7 @@ impl std::iter::Iterator for LazyCatalogIter {
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(string) = self.lines.next() {
             Some(match string {
-                Ok(ok) => serde_json::from_str(&ok).context(errors::read::JsonSnafu {
-                    path: self.path.clone(),
-                }),
+                Ok(ok) => parse_entry(&ok, self.definitions, &self.path),
                 Err(err) => Err(err).context(errors::read::IoSnafu {
                     path: self.path.clone(),
                 }),
@@ -142,24 +507,49 @@ impl std::iter::Iterator for LazyCatalogIter {
 enum InnerCatalogIter {
     Content(ContentCatalogIter),
     Lazy(LazyCatalogIter),
+    #[cfg(feature = "parallel")]
+    Parallel(crate::parallel::ParallelLines<CatalogEntry>),
 }
 
 pub struct CatalogIter {
+    about: AboutCataloger,
     inner: InnerCatalogIter,
 }
 
 impl CatalogIter {
     fn from_data(data: CatalogerData) -> Self {
         Self {
+            about: data.cataloger.clone(),
             inner: InnerCatalogIter::Content(ContentCatalogIter::from_data(data)),
         }
     }
 
-    fn from_lines(lines: Lines, path: std::path::PathBuf) -> Self {
+    fn from_lines(
+        about: AboutCataloger,
+        lines: Lines,
+        path: std::path::PathBuf,
+        options: &ReadOptions,
+    ) -> Self {
+        let definitions = options.schema(CATALOG_DEFINITIONS);
+        #[cfg(feature = "parallel")]
+        if let Some(parallel) = &options.parallel {
+            let iter =
+                crate::parallel::ParallelLines::from_lines(lines, path, definitions, parallel);
+            return Self {
+                about,
+                inner: InnerCatalogIter::Parallel(iter),
+            };
+        }
         Self {
-            inner: InnerCatalogIter::Lazy(LazyCatalogIter::from_lines(lines, path)),
+            about,
+            inner: InnerCatalogIter::Lazy(LazyCatalogIter::from_lines(lines, path, definitions)),
         }
     }
+
+    /// Returns the header describing the provider of the entries.
+    pub fn about(&self) -> &AboutCataloger {
+        &self.about
+    }
 }
 
 impl std::iter::Iterator for CatalogIter {
@@ -169,13 +559,14 @@ impl std::iter::Iterator for CatalogIter {
         match &mut self.inner {
             InnerCatalogIter::Content(iter) => iter.next(),
             InnerCatalogIter::Lazy(iter) => iter.next(),
+            #[cfg(feature = "parallel")]
+            InnerCatalogIter::Parallel(iter) => iter.next(),
         }
     }
 }
 
 struct ContentProducerIter {
-    content: Vec<ProducerEntry>,
-    index: usize,
+    content: std::vec::IntoIter<ProducerEntry>,
 }
 
 impl ContentProducerIter {
@@ -187,7 +578,9 @@ impl ContentProducerIter {
         for reviewer in data.reviewers {
             content.push(ProducerEntry::Reviewer(reviewer))
         }
-        Self { content, index: 0 }
+        Self {
+            content: content.into_iter(),
+        }
     }
 }
 
@@ -195,19 +588,31 @@ impl std::iter::Iterator for ContentProducerIter {
     type Item = Result<ProducerEntry, errors::ReadError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.index += 1;
-        self.content.get(self.index - 1).map(|e| Ok(e.clone()))
+        self.content.next().map(Ok)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.content.size_hint()
     }
 }
 
 struct LazyProducerIter {
     path: std::path::PathBuf,
     lines: Lines,
+    definitions: Option<EntryDefinitions>,
 }
 
 impl LazyProducerIter {
-    fn from_lines(lines: Lines, path: std::path::PathBuf) -> Self {
-        Self { lines, path }
+    fn from_lines(
+        lines: Lines,
+        path: std::path::PathBuf,
+        definitions: Option<EntryDefinitions>,
+    ) -> Self {
+        Self {
+            lines,
+            path,
+            definitions,
+        }
     }
 }
 
@@ -217,9 +622,7 @@ impl std::iter::Iterator for LazyProducerIter {
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(string) = self.lines.next() {
             Some(match string {
-                Ok(ok) => serde_json::from_str(&ok).context(errors::read::JsonSnafu {
-                    path: self.path.clone(),
-                }),
+                Ok(ok) => parse_entry(&ok, self.definitions, &self.path),
                 Err(err) => Err(err).context(errors::read::IoSnafu {
                     path: self.path.clone(),
                 }),
@@ -233,24 +636,49 @@ impl std::iter::Iterator for LazyProducerIter {
 enum InnerProducerIter {
     Content(ContentProducerIter),
     Lazy(LazyProducerIter),
+    #[cfg(feature = "parallel")]
+    Parallel(crate::parallel::ParallelLines<ProducerEntry>),
 }
 
 pub struct ProducerIter {
+    about: AboutProducer,
     inner: InnerProducerIter,
 }
 
 impl ProducerIter {
     fn from_data(data: ProducerData) -> Self {
         Self {
+            about: data.producer.clone(),
             inner: InnerProducerIter::Content(ContentProducerIter::from_data(data)),
         }
     }
 
-    fn from_lines(lines: Lines, path: std::path::PathBuf) -> Self {
+    fn from_lines(
+        about: AboutProducer,
+        lines: Lines,
+        path: std::path::PathBuf,
+        options: &ReadOptions,
+    ) -> Self {
+        let definitions = options.schema(PRODUCER_DEFINITIONS);
+        #[cfg(feature = "parallel")]
+        if let Some(parallel) = &options.parallel {
+            let iter =
+                crate::parallel::ParallelLines::from_lines(lines, path, definitions, parallel);
+            return Self {
+                about,
+                inner: InnerProducerIter::Parallel(iter),
+            };
+        }
         Self {
-            inner: InnerProducerIter::Lazy(LazyProducerIter::from_lines(lines, path)),
+            about,
+            inner: InnerProducerIter::Lazy(LazyProducerIter::from_lines(lines, path, definitions)),
         }
     }
+
+    /// Returns the header describing the provider of the entries.
+    pub fn about(&self) -> &AboutProducer {
+        &self.about
+    }
 }
 
 impl std::iter::Iterator for ProducerIter {
@@ -260,13 +688,14 @@ impl std::iter::Iterator for ProducerIter {
         match &mut self.inner {
             InnerProducerIter::Content(iter) => iter.next(),
             InnerProducerIter::Lazy(iter) => iter.next(),
+            #[cfg(feature = "parallel")]
+            InnerProducerIter::Parallel(iter) => iter.next(),
         }
     }
 }
 
 struct ContentReviewIter {
-    content: Vec<ReviewEntry>,
-    index: usize,
+    content: std::vec::IntoIter<ReviewEntry>,
 }
 
 impl ContentReviewIter {
@@ -278,7 +707,9 @@ impl ContentReviewIter {
         for producer in data.producers {
             content.push(ReviewEntry::Producer(producer))
         }
-        Self { content, index: 0 }
+        Self {
+            content: content.into_iter(),
+        }
     }
 }
 
@@ -286,19 +717,31 @@ impl std::iter::Iterator for ContentReviewIter {
     type Item = Result<ReviewEntry, errors::ReadError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.index += 1;
-        self.content.get(self.index - 1).map(|e| Ok(e.clone()))
+        self.content.next().map(Ok)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.content.size_hint()
     }
 }
 
 struct LazyReviewIter {
     path: std::path::PathBuf,
     lines: Lines,
+    definitions: Option<EntryDefinitions>,
 }
 
 impl LazyReviewIter {
-    fn from_lines(lines: Lines, path: std::path::PathBuf) -> Self {
-        Self { lines, path }
+    fn from_lines(
+        lines: Lines,
+        path: std::path::PathBuf,
+        definitions: Option<EntryDefinitions>,
+    ) -> Self {
+        Self {
+            lines,
+            path,
+            definitions,
+        }
     }
 }
 
@@ -308,9 +751,7 @@ impl std::iter::Iterator for LazyReviewIter {
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(string) = self.lines.next() {
             Some(match string {
-                Ok(ok) => serde_json::from_str(&ok).context(errors::read::JsonSnafu {
-                    path: self.path.clone(),
-                }),
+                Ok(ok) => parse_entry(&ok, self.definitions, &self.path),
                 Err(err) => Err(err).context(errors::read::IoSnafu {
                     path: self.path.clone(),
                 }),
@@ -324,24 +765,49 @@ impl std::iter::Iterator for LazyReviewIter {
 enum InnerReviewIter {
     Content(ContentReviewIter),
     Lazy(LazyReviewIter),
+    #[cfg(feature = "parallel")]
+    Parallel(crate::parallel::ParallelLines<ReviewEntry>),
 }
 
 pub struct ReviewIter {
+    about: AboutReviewer,
     inner: InnerReviewIter,
 }
 
 impl ReviewIter {
     fn from_data(data: ReviewerData) -> Self {
         Self {
+            about: data.reviewer.clone(),
             inner: InnerReviewIter::Content(ContentReviewIter::from_data(data)),
         }
     }
 
-    fn from_lines(lines: Lines, path: std::path::PathBuf) -> Self {
+    fn from_lines(
+        about: AboutReviewer,
+        lines: Lines,
+        path: std::path::PathBuf,
+        options: &ReadOptions,
+    ) -> Self {
+        let definitions = options.schema(REVIEW_DEFINITIONS);
+        #[cfg(feature = "parallel")]
+        if let Some(parallel) = &options.parallel {
+            let iter =
+                crate::parallel::ParallelLines::from_lines(lines, path, definitions, parallel);
+            return Self {
+                about,
+                inner: InnerReviewIter::Parallel(iter),
+            };
+        }
         Self {
-            inner: InnerReviewIter::Lazy(LazyReviewIter::from_lines(lines, path)),
+            about,
+            inner: InnerReviewIter::Lazy(LazyReviewIter::from_lines(lines, path, definitions)),
         }
     }
+
+    /// Returns the header describing the provider of the entries.
+    pub fn about(&self) -> &AboutReviewer {
+        &self.about
+    }
 }
 
 impl std::iter::Iterator for ReviewIter {
@@ -351,6 +817,8 @@ impl std::iter::Iterator for ReviewIter {
         match &mut self.inner {
             InnerReviewIter::Content(iter) => iter.next(),
             InnerReviewIter::Lazy(iter) => iter.next(),
+            #[cfg(feature = "parallel")]
+            InnerReviewIter::Parallel(iter) => iter.next(),
         }
     }
 }
@@ -361,10 +829,128 @@ pub enum FileIterVariant {
     Review(ReviewIter),
 }
 
+/// A substrate opened by `open_file`.
+pub struct FileIter {
+    pub meta: Meta,
+
+    /// `false` if the substrate is not valid at `ReadOptions::valid_at`.
+    pub is_valid: bool,
+
+    pub entries: FileIterVariant,
+}
+
 pub fn iter_file(path: &std::path::Path) -> Result<FileIterVariant, errors::ReadError> {
+    let file = open_file(path, &ReadOptions::default())?;
+    Ok(file.expect("default options never skip substrates").entries)
+}
+
+/// Opens a substrate exposing its `Meta` next to the entries.
+///
+/// Returns `None` if the substrate was skipped according to the options.
+pub fn open_file(
+    path: &std::path::Path,
+    options: &ReadOptions,
+) -> Result<Option<FileIter>, errors::ReadError> {
+    match defs::get_extension(path) {
+        Some(defs::SubstrateExtension::Yaml) => build_yaml_content_iter(path, options),
+        Some(defs::SubstrateExtension::JsonLines) => build_lines_iter(path, options),
+        None => Err(errors::ReadError::Substrate {
+            source: errors::SubstrateError::UnsupportedExtension,
+            path: path.to_owned(),
+        }),
+    }
+}
+
+/// Reads the whole substrate into memory.
+pub fn read_substrate(path: &std::path::Path) -> Result<Substrate, errors::ReadError> {
+    let file = open_file(path, &ReadOptions::default())?;
+    let file = file.expect("default options never skip substrates");
+    let data = file.entries.collect_data(CollectOptions::default())?;
+    Ok(Substrate {
+        meta: file.meta,
+        data,
+    })
+}
+
+/// Reads the provider header and the entries following the `meta` of a delta in JSON lines.
+fn parse_delta_lines<A: DeserializeOwned, E: DeserializeOwned>(
+    mut lines: Lines,
+    (_, about_definition, entry_definitions): DeltaDefinitions,
+    path: &std::path::Path,
+    options: &ReadOptions,
+) -> Result<(A, Vec<E>), errors::ReadError> {
+    let about = next_line(&mut lines, errors::SubstrateError::NoAbout, path)?;
+    let about = parse_json(&about, options.schema(about_definition), path)?;
+    let definitions = options.schema(entry_definitions);
+    let entries = lines
+        .map(|line| {
+            let line = line.context(errors::read::IoSnafu { path })?;
+            parse_delta_entry(&line, definitions, path)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((about, entries))
+}
+
+/// Reads a delta substrate into memory.
+///
+/// The `meta` is parsed like the one of `open_file`, so `None` is returned if the options skip
+/// the delta. Deltas flagged as invalid are returned as they are.
+pub fn read_delta(
+    path: &std::path::Path,
+    options: &ReadOptions,
+) -> Result<Option<DeltaSubstrate>, errors::ReadError> {
     match defs::get_extension(path) {
-        Some(defs::SubstrateExtension::Yaml) => build_yaml_content_iter(path),
-        Some(defs::SubstrateExtension::JsonLines) => build_lines_iter(path),
+        Some(defs::SubstrateExtension::Yaml) => {
+            let contents = std::fs::read_to_string(path).context(errors::read::IoSnafu { path })?;
+            let mut deserializer = serde_yaml::Deserializer::from_str(&contents);
+            let header = next_document(&mut deserializer, errors::SubstrateError::NoMeta, path)?;
+            let meta: DeltaMeta = parse_yaml(header, options.schema("meta"), path)?;
+            if options.check_validity(&meta.meta).is_none() {
+                return Ok(None);
+            }
+            let data = next_document(&mut deserializer, errors::SubstrateError::NoData, path)?;
+            let data = match meta.meta.variant {
+                ProviderVariant::Cataloger => {
+                    let definitions = options.schema(CATALOG_DELTA_DEFINITIONS);
+                    DeltaData::Cataloger(parse_yaml_delta(data, definitions, path)?)
+                }
+                ProviderVariant::Producer => {
+                    let definitions = options.schema(PRODUCER_DELTA_DEFINITIONS);
+                    DeltaData::Producer(parse_yaml_delta(data, definitions, path)?)
+                }
+                ProviderVariant::Reviewer => {
+                    let definitions = options.schema(REVIEW_DELTA_DEFINITIONS);
+                    DeltaData::Reviewer(parse_yaml_delta(data, definitions, path)?)
+                }
+            };
+            Ok(Some(DeltaSubstrate { meta, data }))
+        }
+        Some(defs::SubstrateExtension::JsonLines) => {
+            let mut lines = open_lines(path)?;
+            let meta = next_line(&mut lines, errors::SubstrateError::NoMeta, path)?;
+            let meta: DeltaMeta = parse_json(&meta, options.schema("meta"), path)?;
+            if options.check_validity(&meta.meta).is_none() {
+                return Ok(None);
+            }
+            let data = match meta.meta.variant {
+                ProviderVariant::Cataloger => {
+                    let (cataloger, entries) =
+                        parse_delta_lines(lines, CATALOG_DELTA_DEFINITIONS, path, options)?;
+                    DeltaData::Cataloger(CatalogerDelta { cataloger, entries })
+                }
+                ProviderVariant::Producer => {
+                    let (producer, entries) =
+                        parse_delta_lines(lines, PRODUCER_DELTA_DEFINITIONS, path, options)?;
+                    DeltaData::Producer(ProducerDelta { producer, entries })
+                }
+                ProviderVariant::Reviewer => {
+                    let (reviewer, entries) =
+                        parse_delta_lines(lines, REVIEW_DELTA_DEFINITIONS, path, options)?;
+                    DeltaData::Reviewer(ReviewerDelta { reviewer, entries })
+                }
+            };
+            Ok(Some(DeltaSubstrate { meta, data }))
+        }
         None => Err(errors::ReadError::Substrate {
             source: errors::SubstrateError::UnsupportedExtension,
             path: path.to_owned(),
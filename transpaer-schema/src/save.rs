@@ -28,16 +28,12 @@ pub fn save_cataloger(
                 .context(errors::save::JsonLinesSnafu { path })?;
             serde_jsonlines::append_json_lines(
                 path,
-                data.products
-                    .iter()
-                    .map(|p| data::CatalogEntry::Product(p.clone())),
+                data.products.iter().map(data::CatalogEntryRef::Product),
             )
             .context(errors::save::JsonLinesSnafu { path })?;
             serde_jsonlines::append_json_lines(
                 path,
-                data.producers
-                    .iter()
-                    .map(|p| data::CatalogEntry::Producer(p.clone())),
+                data.producers.iter().map(data::CatalogEntryRef::Producer),
             )
             .context(errors::save::JsonLinesSnafu { path })?;
             Ok(())
@@ -72,16 +68,12 @@ pub fn save_producer(
                 .context(errors::save::JsonLinesSnafu { path })?;
             serde_jsonlines::append_json_lines(
                 path,
-                data.products
-                    .iter()
-                    .map(|p| data::ProducerEntry::Product(p.clone())),
+                data.products.iter().map(data::ProducerEntryRef::Product),
             )
             .context(errors::save::JsonLinesSnafu { path })?;
             serde_jsonlines::append_json_lines(
                 path,
-                data.reviewers
-                    .iter()
-                    .map(|r| data::ProducerEntry::Reviewer(r.clone())),
+                data.reviewers.iter().map(data::ProducerEntryRef::Reviewer),
             )
             .context(errors::save::JsonLinesSnafu { path })?;
             Ok(())
@@ -116,16 +108,12 @@ pub fn save_reviewer(
                 .context(errors::save::JsonLinesSnafu { path })?;
             serde_jsonlines::append_json_lines(
                 path,
-                data.products
-                    .iter()
-                    .map(|p| data::ReviewEntry::Product(p.clone())),
+                data.products.iter().map(data::ReviewEntryRef::Product),
             )
             .context(errors::save::JsonLinesSnafu { path })?;
             serde_jsonlines::append_json_lines(
                 path,
-                data.producers
-                    .iter()
-                    .map(|p| data::ReviewEntry::Producer(p.clone())),
+                data.producers.iter().map(data::ReviewEntryRef::Producer),
             )
             .context(errors::save::JsonLinesSnafu { path })?;
             Ok(())
@@ -144,3 +132,60 @@ impl crate::data::Substrate {
         }
     }
 }
+
+/// Appends the provider header of a delta and then the upsert and tombstone entries in their
+/// original order.
+fn append_delta_lines<A: Serialize, E: Serialize>(
+    path: &std::path::Path,
+    about: &A,
+    entries: &[E],
+) -> Result<(), errors::SaveError> {
+    serde_jsonlines::append_json_lines(path, [about])
+        .context(errors::save::JsonLinesSnafu { path })?;
+    serde_jsonlines::append_json_lines(path, entries)
+        .context(errors::save::JsonLinesSnafu { path })?;
+    Ok(())
+}
+
+impl crate::delta::DeltaSubstrate {
+    /// Saves the delta as two YAML documents or as JSON lines with the `meta` first.
+    pub fn save(&self, path: &std::path::Path) -> Result<(), errors::SaveError> {
+        use crate::delta::DeltaData;
+        match defs::get_extension(path) {
+            Some(defs::SubstrateExtension::Yaml) => {
+                let mut buffer = Vec::new();
+                let mut serializer = serde_yaml::Serializer::new(&mut buffer);
+
+                self.meta
+                    .serialize(&mut serializer)
+                    .context(errors::save::YamlSnafu { path })?;
+                match &self.data {
+                    DeltaData::Cataloger(data) => data.serialize(&mut serializer),
+                    DeltaData::Producer(data) => data.serialize(&mut serializer),
+                    DeltaData::Reviewer(data) => data.serialize(&mut serializer),
+                }
+                .context(errors::save::YamlSnafu { path })?;
+
+                std::fs::write(path, buffer).context(errors::save::IoSnafu { path })?;
+                Ok(())
+            }
+            Some(defs::SubstrateExtension::JsonLines) => {
+                serde_jsonlines::write_json_lines(path, [&self.meta])
+                    .context(errors::save::JsonLinesSnafu { path })?;
+                match &self.data {
+                    DeltaData::Cataloger(data) => {
+                        append_delta_lines(path, &data.cataloger, &data.entries)
+                    }
+                    DeltaData::Producer(data) => {
+                        append_delta_lines(path, &data.producer, &data.entries)
+                    }
+                    DeltaData::Reviewer(data) => {
+                        append_delta_lines(path, &data.reviewer, &data.entries)
+                    }
+                }
+            }
+            None => Err(errors::SubstrateError::UnsupportedExtension)
+                .context(errors::save::SubstrateSnafu { path }),
+        }
+    }
+}
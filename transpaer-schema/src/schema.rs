@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::errors::SchemaViolation;
+
+/// Validates JSON values against the definitions of the bundled schema.
+pub struct SchemaValidator {
+    validators: HashMap<String, jsonschema::Validator>,
+}
+
+impl SchemaValidator {
+    /// Compiles a validator for every `$defs` entry of the bundled schema.
+    pub fn new() -> Self {
+        let schema: serde_json::Value =
+            serde_json::from_str(crate::JSON_SCHEMA).expect("the bundled schema is valid JSON");
+        let definitions = schema["$defs"]
+            .as_object()
+            .expect("the bundled schema has `$defs`");
+
+        let mut validators = HashMap::with_capacity(definitions.len());
+        for name in definitions.keys() {
+            let mut root = schema.clone();
+            root["$ref"] = serde_json::Value::String(format!("#/$defs/{name}"));
+            let validator =
+                jsonschema::validator_for(&root).expect("the bundled schema is a valid schema");
+            validators.insert(name.clone(), validator);
+        }
+        Self { validators }
+    }
+
+    /// Returns the violations of the `$defs` entry with the given name.
+    ///
+    /// Panics if the schema has no such definition.
+    pub fn validate(&self, definition: &str, value: &serde_json::Value) -> Vec<SchemaViolation> {
+        let validator = self
+            .validators
+            .get(definition)
+            .unwrap_or_else(|| panic!("no `{definition}` definition in the bundled schema"));
+        validator
+            .iter_errors(value)
+            .map(|error| SchemaViolation {
+                pointer: error.instance_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl Default for SchemaValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns a shared validator, compiling it on first use.
+pub fn validator() -> &'static SchemaValidator {
+    static VALIDATOR: std::sync::OnceLock<SchemaValidator> = std::sync::OnceLock::new();
+    VALIDATOR.get_or_init(SchemaValidator::new)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let valid = serde_json::json!({ "value": 50 });
+        assert_eq!(validator().validate("scoreReview", &valid), Vec::new());
+
+        let invalid = serde_json::json!({ "ean": ["1", "1"], "wiki": [5] });
+        let pointers: Vec<_> = validator()
+            .validate("productIds", &invalid)
+            .into_iter()
+            .map(|v| v.pointer)
+            .collect();
+        assert_eq!(pointers, vec!["/ean".to_owned(), "/wiki/0".to_owned()]);
+    }
+}
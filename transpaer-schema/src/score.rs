@@ -0,0 +1,110 @@
+impl crate::AboutScoreReview {
+    /// Maps a score from this scale onto `[0, 1]`.
+    ///
+    /// Scores outside of `min..=max` are clamped. Returns `None` if the scale is empty or
+    /// inverted, since it cannot tell scores apart.
+    pub fn normalized_score(&self, value: i64) -> Option<f64> {
+        if self.min >= self.max {
+            return None;
+        }
+        // Differences of extreme bounds do not fit in `i64`.
+        let (value, min, max) = (
+            i128::from(value.clamp(self.min, self.max)),
+            i128::from(self.min),
+            i128::from(self.max),
+        );
+        Some((value - min) as f64 / (max - min) as f64)
+    }
+}
+
+impl crate::Review {
+    /// Returns the score mapped onto `[0, 1]` using the reviewer's declared scale.
+    ///
+    /// Returns `None` for certifications and mentions, and if the reviewer declares no score scale.
+    pub fn normalized_score(&self, reviewer: &crate::AboutReviewer) -> Option<f64> {
+        match (self, &reviewer.reviews) {
+            (Self::ScoreReview(score), Some(crate::AboutReview::ScoreReview(scale))) => {
+                scale.normalized_score(score.value)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl crate::ReviewProduct {
+    /// Returns the review score mapped onto `[0, 1]`, see `Review::normalized_score`.
+    pub fn normalized_score(&self, reviewer: &crate::AboutReviewer) -> Option<f64> {
+        self.review.as_ref()?.normalized_score(reviewer)
+    }
+}
+
+impl crate::ReviewProducer {
+    /// Returns the review score mapped onto `[0, 1]`, see `Review::normalized_score`.
+    pub fn normalized_score(&self, reviewer: &crate::AboutReviewer) -> Option<f64> {
+        self.review.as_ref()?.normalized_score(reviewer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn scale(min: i64, max: i64) -> crate::AboutScoreReview {
+        crate::AboutScoreReview { div: 1, max, min }
+    }
+
+    fn reviewer(reviews: Option<crate::AboutReview>) -> crate::AboutReviewer {
+        crate::AboutReviewer {
+            description: "Reviewer".to_owned(),
+            id: "reviewer".to_owned(),
+            name: "Reviewer".to_owned(),
+            reviews,
+            website: "https://example.com".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_scale() {
+        assert_eq!(scale(0, 100).normalized_score(0), Some(0.0));
+        assert_eq!(scale(0, 100).normalized_score(25), Some(0.25));
+        assert_eq!(scale(0, 100).normalized_score(100), Some(1.0));
+        assert_eq!(scale(-5, 5).normalized_score(0), Some(0.5));
+    }
+
+    #[test]
+    fn test_scale_out_of_range() {
+        assert_eq!(scale(1, 5).normalized_score(0), Some(0.0));
+        assert_eq!(scale(1, 5).normalized_score(10), Some(1.0));
+    }
+
+    #[test]
+    fn test_extreme_scale() {
+        let extreme = scale(i64::MIN, i64::MAX);
+        assert_eq!(extreme.normalized_score(i64::MIN), Some(0.0));
+        assert_eq!(extreme.normalized_score(0), Some(0.5));
+        assert_eq!(extreme.normalized_score(i64::MAX), Some(1.0));
+        assert_eq!(scale(i64::MIN, 0).normalized_score(i64::MAX), Some(1.0));
+    }
+
+    #[test]
+    fn test_degenerate_scale() {
+        assert_eq!(scale(3, 3).normalized_score(3), None);
+        assert_eq!(scale(5, 1).normalized_score(3), None);
+    }
+
+    #[test]
+    fn test_review() {
+        let score = crate::Review::ScoreReview(crate::ScoreReview { value: 4 });
+        let mention = crate::Review::Mention(crate::Mention {
+            title: "Title".to_owned(),
+            url: "https://example.com".to_owned(),
+        });
+        let scored = reviewer(Some(crate::AboutReview::ScoreReview(scale(0, 8))));
+        let certifying = reviewer(Some(crate::AboutReview::Certification(
+            crate::AboutCertification(serde_json::Map::new()),
+        )));
+
+        assert_eq!(score.normalized_score(&scored), Some(0.5));
+        assert_eq!(score.normalized_score(&certifying), None);
+        assert_eq!(score.normalized_score(&reviewer(None)), None);
+        assert_eq!(mention.normalized_score(&scored), None);
+    }
+}
@@ -21,7 +21,7 @@ impl crate::ProductAvailability {
 
 impl crate::ProductCategorisation {
     pub fn sort(&mut self) {
-        // TODO: self.categories.sort();
+        self.categories.sort();
     }
 }
 
@@ -41,6 +41,9 @@ impl crate::ProductIds {
 
 impl crate::ProducerIds {
     pub fn sort(&mut self) {
+        if let Some(domains) = &mut self.domains {
+            domains.sort();
+        }
         if let Some(wiki) = &mut self.wiki {
             wiki.sort();
         }
@@ -52,7 +55,18 @@ impl crate::ProducerIds {
 
 impl crate::ProductOrigins {
     pub fn sort(&mut self) {
-        self.producer_ids.sort()
+        self.producer_ids.sort();
+        if let Some(regions) = &mut self.regions {
+            regions.sort();
+        }
+    }
+}
+
+impl crate::ProducerOrigins {
+    pub fn sort(&mut self) {
+        if let Some(regions) = &mut self.regions {
+            regions.sort();
+        }
     }
 }
 
@@ -67,6 +81,37 @@ impl crate::RelatedProducts {
     }
 }
 
+impl crate::Shopping {
+    pub fn sort(&mut self) {
+        self.0
+            .sort_by(|a, b| (a.shop, &a.id, &a.description).cmp(&(b.shop, &b.id, &b.description)));
+    }
+}
+
+impl crate::Reports {
+    pub fn sort(&mut self) {
+        self.0
+            .sort_by(|a, b| (&a.title, &a.url).cmp(&(&b.title, &b.url)));
+    }
+}
+
+impl crate::Meta {
+    pub fn sort(&mut self) {
+        self.authors.sort();
+    }
+}
+
+impl crate::AboutProducer {
+    pub fn sort(&mut self) {
+        self.ids.sort();
+        self.images.sort();
+        if let Some(origins) = &mut self.origins {
+            origins.sort();
+        }
+        self.websites.sort();
+    }
+}
+
 impl crate::CatalogProduct {
     pub fn sort(&mut self) {
         if let Some(availability) = &mut self.availability {
@@ -84,6 +129,9 @@ impl crate::CatalogProduct {
         if let Some(related) = &mut self.related {
             related.sort();
         }
+        if let Some(shopping) = &mut self.shopping {
+            shopping.sort();
+        }
     }
 }
 
@@ -92,6 +140,9 @@ impl crate::CatalogProducer {
         self.ids.sort();
         self.images.sort();
         self.names.sort();
+        if let Some(origins) = &mut self.origins {
+            origins.sort();
+        }
         self.websites.sort();
     }
 }
@@ -111,6 +162,9 @@ impl crate::ProducerProduct {
         if let Some(related) = &mut self.related {
             related.sort();
         }
+        if let Some(shopping) = &mut self.shopping {
+            shopping.sort();
+        }
     }
 }
 
@@ -137,6 +191,12 @@ impl crate::ReviewProduct {
         if let Some(related) = &mut self.related {
             related.sort();
         }
+        if let Some(reports) = &mut self.reports {
+            reports.sort();
+        }
+        if let Some(shopping) = &mut self.shopping {
+            shopping.sort();
+        }
     }
 }
 
@@ -145,29 +205,50 @@ impl crate::ReviewProducer {
         self.ids.sort();
         self.images.sort();
         self.names.sort();
+        if let Some(origins) = &mut self.origins {
+            origins.sort();
+        }
+        if let Some(reports) = &mut self.reports {
+            reports.sort();
+        }
         self.websites.sort();
     }
 }
 
+/// Orders entities by their `id`.
+///
+/// Entities sharing an `id` are ordered by their serialized form, so the order does not depend
+/// on the input order. Expects the entities to be sorted already.
+macro_rules! sort_by_id {
+    ($entities:expr) => {
+        $entities.sort_by_cached_key(|e| (e.id.clone(), serde_json::to_string(e).ok()))
+    };
+}
+
 impl crate::CatalogerData {
     pub fn sort(&mut self) {
         for producer in &mut self.producers {
             producer.sort();
         }
+        sort_by_id!(self.producers);
         for product in &mut self.products {
             product.sort();
         }
+        sort_by_id!(self.products);
     }
 }
 
 impl crate::ProducerData {
     pub fn sort(&mut self) {
+        self.producer.sort();
         for product in &mut self.products {
             product.sort();
         }
+        sort_by_id!(self.products);
         for reviewer in &mut self.reviewers {
             reviewer.sort();
         }
+        sort_by_id!(self.reviewers);
     }
 }
 
@@ -176,14 +257,17 @@ impl crate::ReviewerData {
         for producer in &mut self.producers {
             producer.sort();
         }
+        sort_by_id!(self.producers);
         for product in &mut self.products {
             product.sort();
         }
+        sort_by_id!(self.products);
     }
 }
 
 impl crate::data::Substrate {
     pub fn sort(&mut self) {
+        self.meta.sort();
         match &mut self.data {
             crate::data::Data::Cataloger(data) => data.sort(),
             crate::data::Data::Producer(data) => data.sort(),
@@ -0,0 +1,375 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Serializer};
+
+use crate::{
+    data::{CatalogEntry, ProducerEntry, ReviewEntry},
+    errors,
+    read::FileIterVariant,
+    Data,
+};
+
+/// Counts of one kind of entity and of how often their fields are filled.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntityStats {
+    /// Number of entities.
+    pub count: usize,
+
+    /// Number of entities with a present and non-empty value, per field.
+    pub filled: BTreeMap<&'static str, usize>,
+
+    /// Number of entities per length of the list, per list field.
+    pub list_lengths: BTreeMap<&'static str, BTreeMap<usize, usize>>,
+}
+
+impl EntityStats {
+    /// Share of entities with the field filled, `None` for unknown fields or if there are no
+    /// entities.
+    pub fn fill_rate(&self, field: &str) -> Option<f64> {
+        let filled = *self.filled.get(field)?;
+        (self.count > 0).then(|| filled as f64 / self.count as f64)
+    }
+
+    fn field(&mut self, name: &'static str, filled: bool) {
+        *self.filled.entry(name).or_default() += usize::from(filled);
+    }
+
+    fn list(&mut self, name: &'static str, len: usize) {
+        self.field(name, len > 0);
+        *self
+            .list_lengths
+            .entry(name)
+            .or_default()
+            .entry(len)
+            .or_default() += 1;
+    }
+
+    fn optional_list<T>(&mut self, name: &'static str, list: &Option<Vec<T>>) {
+        self.list(name, list.as_ref().map_or(0, Vec::len));
+    }
+
+    fn product_ids(&mut self, ids: &crate::ProductIds) {
+        self.optional_list("ean", &ids.ean);
+        self.optional_list("gtin", &ids.gtin);
+        self.optional_list("wiki", &ids.wiki);
+    }
+
+    fn producer_ids(&mut self, ids: &crate::ProducerIds) {
+        self.optional_list("vat", &ids.vat);
+        self.optional_list("domains", &ids.domains);
+        self.optional_list("wiki", &ids.wiki);
+    }
+
+    fn categories(&mut self, categorisation: Option<&crate::ProductCategorisation>) {
+        self.list(
+            "categories",
+            categorisation.map_or(0, |c| c.categories.len()),
+        );
+    }
+}
+
+#[derive(Serialize)]
+struct FieldReport {
+    filled: usize,
+    fill_rate: f64,
+}
+
+impl Serialize for EntityStats {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            count: usize,
+            fields: BTreeMap<&'static str, FieldReport>,
+            list_lengths: &'a BTreeMap<&'static str, BTreeMap<usize, usize>>,
+        }
+
+        let fields = self
+            .filled
+            .iter()
+            .map(|(&name, &filled)| {
+                let fill_rate = self.fill_rate(name).unwrap_or(0.0);
+                (name, FieldReport { filled, fill_rate })
+            })
+            .collect();
+        Report {
+            count: self.count,
+            fields,
+            list_lengths: &self.list_lengths,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Counts, field fill rates and list length histograms of the entities of a substrate.
+///
+/// Built in a single pass by feeding it the entries one by one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    pub producers: EntityStats,
+    pub products: EntityStats,
+    pub reviewers: EntityStats,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_catalog_producer(&mut self, producer: &crate::CatalogProducer) {
+        let stats = &mut self.producers;
+        stats.count += 1;
+        stats.producer_ids(&producer.ids);
+        stats.list("names", producer.names.len());
+        stats.list("images", producer.images.len());
+        stats.list("websites", producer.websites.len());
+        stats.field("description", producer.description.is_some());
+        stats.field("origins", producer.origins.is_some());
+    }
+
+    pub fn add_catalog_product(&mut self, product: &crate::CatalogProduct) {
+        let stats = &mut self.products;
+        stats.count += 1;
+        stats.product_ids(&product.ids);
+        stats.list("names", product.names.len());
+        stats.list("images", product.images.len());
+        stats.categories(product.categorisation.as_ref());
+        stats.field("description", product.description.is_some());
+        stats.field("availability", product.availability.is_some());
+        stats.field("origins", product.origins.is_some());
+        stats.field("related", product.related.is_some());
+        stats.field("shopping", product.shopping.is_some());
+    }
+
+    /// Adds the producer described by the header of a producer substrate.
+    pub fn add_producer(&mut self, producer: &crate::AboutProducer) {
+        let stats = &mut self.producers;
+        stats.count += 1;
+        stats.producer_ids(&producer.ids);
+        stats.list("names", 1);
+        stats.list("images", producer.images.len());
+        stats.list("websites", producer.websites.len());
+        stats.field("description", producer.description.is_some());
+        stats.field("origins", producer.origins.is_some());
+    }
+
+    pub fn add_producer_product(&mut self, product: &crate::ProducerProduct) {
+        let stats = &mut self.products;
+        stats.count += 1;
+        stats.product_ids(&product.ids);
+        stats.list("names", product.names.len());
+        stats.list("images", product.images.len());
+        stats.categories(Some(&product.categorisation));
+        stats.field("description", !product.description.is_empty());
+        stats.field("availability", product.availability.is_some());
+        stats.field("origins", product.origins.is_some());
+        stats.field("related", product.related.is_some());
+        stats.field("shopping", product.shopping.is_some());
+    }
+
+    pub fn add_producer_reviewer(&mut self, reviewer: &crate::ProducerReviewer) {
+        let stats = &mut self.reviewers;
+        stats.count += 1;
+        stats.list("names", reviewer.names.len());
+        stats.field("description", reviewer.description.is_some());
+    }
+
+    /// Adds the reviewer described by the header of a reviewer substrate.
+    pub fn add_reviewer(&mut self, reviewer: &crate::AboutReviewer) {
+        let stats = &mut self.reviewers;
+        stats.count += 1;
+        stats.list("names", 1);
+        stats.field("description", !reviewer.description.is_empty());
+        stats.field("reviews", reviewer.reviews.is_some());
+    }
+
+    pub fn add_review_producer(&mut self, producer: &crate::ReviewProducer) {
+        let stats = &mut self.producers;
+        stats.count += 1;
+        stats.producer_ids(&producer.ids);
+        stats.list("names", producer.names.len());
+        stats.list("images", producer.images.len());
+        stats.list("websites", producer.websites.len());
+        stats.field("description", producer.description.is_some());
+        stats.field("origins", producer.origins.is_some());
+        stats.field("review", producer.review.is_some());
+    }
+
+    pub fn add_review_product(&mut self, product: &crate::ReviewProduct) {
+        let stats = &mut self.products;
+        stats.count += 1;
+        stats.product_ids(&product.ids);
+        stats.list("names", product.names.len());
+        stats.list("images", product.images.len());
+        stats.categories(product.categorisation.as_ref());
+        stats.field("availability", product.availability.is_some());
+        stats.field("origins", product.origins.is_some());
+        stats.field("related", product.related.is_some());
+        stats.field("shopping", product.shopping.is_some());
+        stats.field("review", product.review.is_some());
+    }
+
+    pub fn add_catalog_entry(&mut self, entry: &CatalogEntry) {
+        match entry {
+            CatalogEntry::Producer(producer) => self.add_catalog_producer(producer),
+            CatalogEntry::Product(product) => self.add_catalog_product(product),
+        }
+    }
+
+    pub fn add_producer_entry(&mut self, entry: &ProducerEntry) {
+        match entry {
+            ProducerEntry::Product(product) => self.add_producer_product(product),
+            ProducerEntry::Reviewer(reviewer) => self.add_producer_reviewer(reviewer),
+        }
+    }
+
+    pub fn add_review_entry(&mut self, entry: &ReviewEntry) {
+        match entry {
+            ReviewEntry::Producer(producer) => self.add_review_producer(producer),
+            ReviewEntry::Product(product) => self.add_review_product(product),
+        }
+    }
+}
+
+impl Data {
+    /// Computes the statistics of the entities, including the producer of a producer substrate
+    /// and the reviewer of a reviewer substrate.
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::new();
+        match self {
+            Data::Cataloger(data) => {
+                data.producers
+                    .iter()
+                    .for_each(|p| stats.add_catalog_producer(p));
+                data.products
+                    .iter()
+                    .for_each(|p| stats.add_catalog_product(p));
+            }
+            Data::Producer(data) => {
+                stats.add_producer(&data.producer);
+                data.products
+                    .iter()
+                    .for_each(|p| stats.add_producer_product(p));
+                data.reviewers
+                    .iter()
+                    .for_each(|r| stats.add_producer_reviewer(r));
+            }
+            Data::Reviewer(data) => {
+                stats.add_reviewer(&data.reviewer);
+                data.producers
+                    .iter()
+                    .for_each(|p| stats.add_review_producer(p));
+                data.products
+                    .iter()
+                    .for_each(|p| stats.add_review_product(p));
+            }
+        }
+        stats
+    }
+}
+
+impl FileIterVariant {
+    /// Computes the statistics while reading the entries, without keeping them in memory.
+    ///
+    /// Stops at the first read error.
+    pub fn stats(self) -> Result<Stats, errors::ReadError> {
+        let mut stats = Stats::new();
+        match self {
+            FileIterVariant::Catalog(iter) => {
+                for entry in iter {
+                    stats.add_catalog_entry(&entry?);
+                }
+            }
+            FileIterVariant::Producer(iter) => {
+                stats.add_producer(iter.about());
+                for entry in iter {
+                    stats.add_producer_entry(&entry?);
+                }
+            }
+            FileIterVariant::Review(iter) => {
+                stats.add_reviewer(iter.about());
+                for entry in iter {
+                    stats.add_review_entry(&entry?);
+                }
+            }
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn product(ean: &[&str], images: usize) -> crate::CatalogProduct {
+        crate::CatalogProduct {
+            availability: None,
+            categorisation: None,
+            description: None,
+            id: "id".to_owned(),
+            ids: crate::ProductIds {
+                ean: (!ean.is_empty()).then(|| ean.iter().map(|e| e.to_string()).collect()),
+                gtin: None,
+                wiki: None,
+            },
+            images: vec!["image.png".to_owned(); images],
+            names: vec!["Name".to_owned()],
+            origins: None,
+            related: None,
+            shopping: None,
+        }
+    }
+
+    #[test]
+    fn test_catalog_stats() {
+        let mut stats = Stats::new();
+        for entry in [
+            CatalogEntry::Product(product(&["5901234123457"], 2)),
+            CatalogEntry::Product(product(&[], 0)),
+            CatalogEntry::Product(product(&["5901234123457", "96385074"], 2)),
+            CatalogEntry::Product(product(&[], 1)),
+        ] {
+            stats.add_catalog_entry(&entry);
+        }
+
+        let products = &stats.products;
+        assert_eq!(products.count, 4);
+        assert_eq!(products.filled["ean"], 2);
+        assert_eq!(products.fill_rate("ean"), Some(0.5));
+        assert_eq!(products.fill_rate("images"), Some(0.75));
+        assert_eq!(products.fill_rate("availability"), Some(0.0));
+        assert_eq!(products.fill_rate("unknown"), None);
+        assert_eq!(
+            products.list_lengths["images"],
+            BTreeMap::from([(0, 1), (1, 1), (2, 2)])
+        );
+        assert_eq!(stats.producers.count, 0);
+        assert_eq!(stats.producers.fill_rate("vat"), None);
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["products"]["count"], 4);
+        assert_eq!(json["products"]["fields"]["ean"]["filled"], 2);
+        assert_eq!(json["products"]["fields"]["ean"]["fill_rate"], 0.5);
+        assert_eq!(json["products"]["list_lengths"]["ean"]["2"], 1);
+    }
+
+    #[test]
+    fn test_reviewer_stats() {
+        let data = Data::Reviewer(crate::ReviewerData {
+            producers: Vec::new(),
+            products: Vec::new(),
+            reviewer: crate::AboutReviewer {
+                description: String::new(),
+                id: "reviewer".to_owned(),
+                name: "Reviewer".to_owned(),
+                reviews: None,
+                website: "https://example.com".to_owned(),
+            },
+        });
+
+        let stats = data.stats();
+        assert_eq!(stats.reviewers.count, 1);
+        assert_eq!(stats.reviewers.fill_rate("names"), Some(1.0));
+        assert_eq!(stats.reviewers.fill_rate("description"), Some(0.0));
+        assert_eq!(stats.producers.count, 0);
+    }
+}
@@ -0,0 +1,693 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    validate::{Finding, Rule},
+    EntityKind,
+};
+
+/// Field of `RelatedProducts` holding a succession link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LinkField {
+    FollowedBy,
+    PrecededBy,
+}
+
+impl LinkField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FollowedBy => "followed_by",
+            Self::PrecededBy => "preceded_by",
+        }
+    }
+}
+
+/// A link declared by one product but not mirrored by the product it points at.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MissingLink {
+    /// Product lacking the reverse link.
+    pub product: String,
+
+    /// Field the reverse link should be added to.
+    pub field: LinkField,
+
+    /// Product the reverse link should point at.
+    pub target: String,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    followed_by: BTreeSet<String>,
+    preceded_by: BTreeSet<String>,
+}
+
+/// Product succession graph of a single substrate.
+///
+/// Edges point from a product to its successor. An edge exists if either end declares it, so
+/// links declared only on one side still take part in cycle and branching detection. Links to
+/// products missing from the substrate and self-references are ignored.
+#[derive(Debug, Default)]
+pub struct SuccessionGraph {
+    nodes: BTreeMap<String, Node>,
+    successors: BTreeMap<String, BTreeSet<String>>,
+    predecessors: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SuccessionGraph {
+    pub fn new<'a>(
+        products: impl IntoIterator<Item = (&'a str, Option<&'a crate::RelatedProducts>)>,
+    ) -> Self {
+        let mut nodes = BTreeMap::<String, Node>::new();
+        for (id, related) in products {
+            let node = nodes.entry(id.to_owned()).or_default();
+            if let Some(related) = related {
+                node.followed_by
+                    .extend(related.followed_by.iter().flatten().cloned());
+                node.preceded_by
+                    .extend(related.preceded_by.iter().flatten().cloned());
+            }
+        }
+
+        let mut successors = BTreeMap::<String, BTreeSet<String>>::new();
+        let mut predecessors = BTreeMap::<String, BTreeSet<String>>::new();
+        let mut add_edge = |from: &String, to: &String| {
+            if from != to && nodes.contains_key(from) && nodes.contains_key(to) {
+                successors
+                    .entry(from.clone())
+                    .or_default()
+                    .insert(to.clone());
+                predecessors
+                    .entry(to.clone())
+                    .or_default()
+                    .insert(from.clone());
+            }
+        };
+        for (id, node) in &nodes {
+            for next in &node.followed_by {
+                add_edge(id, next);
+            }
+            for previous in &node.preceded_by {
+                add_edge(previous, id);
+            }
+        }
+
+        Self {
+            nodes,
+            successors,
+            predecessors,
+        }
+    }
+
+    /// Returns the direct successors of the product.
+    pub fn successors(&self, id: &str) -> BTreeSet<&str> {
+        self.successors
+            .get(id)
+            .map(|ids| ids.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the direct predecessors of the product.
+    pub fn predecessors(&self, id: &str) -> BTreeSet<&str> {
+        self.predecessors
+            .get(id)
+            .map(|ids| ids.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns links declared on one side only.
+    pub fn missing_links(&self) -> Vec<MissingLink> {
+        let mut result = Vec::new();
+        for (id, node) in &self.nodes {
+            for next in &node.followed_by {
+                if let Some(other) = self.nodes.get(next) {
+                    if next != id && !other.preceded_by.contains(id) {
+                        result.push(MissingLink {
+                            product: next.clone(),
+                            field: LinkField::PrecededBy,
+                            target: id.clone(),
+                        });
+                    }
+                }
+            }
+            for previous in &node.preceded_by {
+                if let Some(other) = self.nodes.get(previous) {
+                    if previous != id && !other.followed_by.contains(id) {
+                        result.push(MissingLink {
+                            product: previous.clone(),
+                            field: LinkField::FollowedBy,
+                            target: id.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        result.sort();
+        result
+    }
+
+    /// Returns all elementary succession cycles, each starting from its smallest product `id`.
+    ///
+    /// Uses Johnson's algorithm inside each strongly connected component with more than one
+    /// product: cycles through each product are searched for among the products with a larger
+    /// `id` only, so that every cycle is found once.
+    pub fn cycles(&self) -> Vec<Vec<&str>> {
+        let mut search = CycleSearch {
+            graph: self,
+            component: BTreeSet::new(),
+            start: "",
+            path: Vec::new(),
+            blocked: BTreeSet::new(),
+            blocked_by: BTreeMap::new(),
+            cycles: BTreeSet::new(),
+        };
+        for component in self.components() {
+            search.component = component;
+            for start in search.component.clone() {
+                search.start = start;
+                search.blocked.clear();
+                search.blocked_by.clear();
+                search.circuit(start);
+            }
+        }
+        search.cycles.into_iter().collect()
+    }
+
+    /// Returns the strongly connected components with more than one product.
+    fn components(&self) -> Vec<BTreeSet<&str>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            next_index: 0,
+            index: HashMap::new(),
+            lowlink: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            calls: Vec::new(),
+            components: Vec::new(),
+        };
+        for root in self.nodes.keys() {
+            if !tarjan.index.contains_key(root.as_str()) {
+                tarjan.run(root);
+            }
+        }
+        tarjan.components
+    }
+
+    /// Reports asymmetric links, cycles and products with several successors or predecessors.
+    pub fn findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for link in self.missing_links() {
+            findings.push(Finding::new(
+                Rule::SuccessionLink,
+                EntityKind::Product,
+                &link.product,
+                format!("/related/{}", link.field.as_str()),
+                format!("Missing reverse link to {:?}", link.target),
+            ));
+        }
+        for cycle in self.cycles() {
+            findings.push(Finding::new(
+                Rule::SuccessionCycle,
+                EntityKind::Product,
+                cycle[0],
+                "/related/followed_by",
+                format!("Succession cycle: {} -> {}", cycle.join(" -> "), cycle[0]),
+            ));
+        }
+        for id in self.nodes.keys() {
+            let successors = self.successors(id);
+            if successors.len() > 1 {
+                findings.push(Finding::new(
+                    Rule::SuccessionFork,
+                    EntityKind::Product,
+                    id,
+                    "/related/followed_by",
+                    format!("Followed by several products: {successors:?}"),
+                ));
+            }
+            let predecessors = self.predecessors(id);
+            if predecessors.len() > 1 {
+                findings.push(Finding::new(
+                    Rule::SuccessionFork,
+                    EntityKind::Product,
+                    id,
+                    "/related/preceded_by",
+                    format!("Preceded by several products: {predecessors:?}"),
+                ));
+            }
+        }
+        findings
+    }
+}
+
+/// State of Tarjan's search for strongly connected components.
+///
+/// Iterates with an explicit stack of calls, so that long succession chains do not overflow the
+/// thread stack.
+struct Tarjan<'a> {
+    graph: &'a SuccessionGraph,
+    next_index: usize,
+    index: HashMap<&'a str, usize>,
+    lowlink: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+
+    /// Products being visited with their successors and the position of the next one to visit.
+    calls: Vec<(&'a str, Vec<&'a str>, usize)>,
+
+    components: Vec<BTreeSet<&'a str>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn visit(&mut self, id: &'a str) {
+        self.index.insert(id, self.next_index);
+        self.lowlink.insert(id, self.next_index);
+        self.next_index += 1;
+        self.stack.push(id);
+        self.on_stack.insert(id);
+        let graph: &'a SuccessionGraph = self.graph;
+        let successors = graph
+            .successors
+            .get(id)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        self.calls.push((id, successors, 0));
+    }
+
+    fn lower(&mut self, id: &'a str, value: usize) {
+        let lowlink = self
+            .lowlink
+            .get_mut(id)
+            .expect("visited products have a lowlink");
+        *lowlink = (*lowlink).min(value);
+    }
+
+    fn run(&mut self, root: &'a str) {
+        self.visit(root);
+        while let Some((id, successors, position)) = self.calls.last_mut() {
+            let id = *id;
+            if let Some(&next) = successors.get(*position) {
+                *position += 1;
+                if !self.index.contains_key(next) {
+                    self.visit(next);
+                } else if self.on_stack.contains(next) {
+                    self.lower(id, self.index[next]);
+                }
+                continue;
+            }
+
+            self.calls.pop();
+            let lowlink = self.lowlink[id];
+            if let Some((parent, _, _)) = self.calls.last() {
+                self.lower(parent, lowlink);
+            }
+            if lowlink == self.index[id] {
+                let mut component = BTreeSet::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    component.insert(member);
+                    if member == id {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    self.components.push(component);
+                }
+            }
+        }
+    }
+}
+
+/// A product on the path of Johnson's search with the successors still to visit.
+struct Frame<'a> {
+    id: &'a str,
+    successors: Vec<&'a str>,
+    position: usize,
+
+    /// Whether a cycle was found through the product.
+    found: bool,
+}
+
+/// State of Johnson's search for elementary cycles within one strongly connected component.
+struct CycleSearch<'a> {
+    graph: &'a SuccessionGraph,
+
+    /// Products of the component searched.
+    component: BTreeSet<&'a str>,
+
+    /// The smallest product of the cycles searched for.
+    start: &'a str,
+
+    path: Vec<&'a str>,
+
+    /// Products that cannot lead back to `start` without passing through `path`.
+    blocked: BTreeSet<&'a str>,
+
+    /// Products to unblock once the key gets unblocked.
+    blocked_by: BTreeMap<&'a str, BTreeSet<&'a str>>,
+
+    cycles: BTreeSet<Vec<&'a str>>,
+}
+
+impl<'a> CycleSearch<'a> {
+    fn successors(&self, id: &str) -> Vec<&'a str> {
+        let graph: &'a SuccessionGraph = self.graph;
+        graph
+            .successors
+            .get(id)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|next| *next >= self.start && self.component.contains(next))
+            .collect()
+    }
+
+    fn enter(&mut self, id: &'a str) -> Frame<'a> {
+        self.path.push(id);
+        self.blocked.insert(id);
+        Frame {
+            id,
+            successors: self.successors(id),
+            position: 0,
+            found: false,
+        }
+    }
+
+    /// Searches for cycles through `start`, with an explicit stack instead of recursion so that
+    /// long paths do not overflow the thread stack.
+    fn circuit(&mut self, start: &'a str) {
+        let mut stack = vec![self.enter(start)];
+        while let Some(frame) = stack.last_mut() {
+            if let Some(&next) = frame.successors.get(frame.position) {
+                frame.position += 1;
+                if next == self.start {
+                    self.cycles.insert(self.path.clone());
+                    frame.found = true;
+                } else if !self.blocked.contains(next) {
+                    let frame = self.enter(next);
+                    stack.push(frame);
+                }
+                continue;
+            }
+
+            let frame = stack.pop().expect("the stack is not empty");
+            if frame.found {
+                self.unblock(frame.id);
+            } else {
+                for next in &frame.successors {
+                    self.blocked_by.entry(next).or_default().insert(frame.id);
+                }
+            }
+            self.path.pop();
+            if let Some(parent) = stack.last_mut() {
+                parent.found |= frame.found;
+            }
+        }
+    }
+
+    fn unblock(&mut self, id: &'a str) {
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            self.blocked.remove(id);
+            for other in self.blocked_by.remove(id).unwrap_or_default() {
+                if self.blocked.contains(other) {
+                    pending.push(other);
+                }
+            }
+        }
+    }
+}
+
+/// Access to the succession links of the different product kinds.
+trait Successive {
+    fn id(&self) -> &str;
+    fn related(&self) -> Option<&crate::RelatedProducts>;
+    fn related_mut(&mut self) -> &mut Option<crate::RelatedProducts>;
+}
+
+macro_rules! impl_successive {
+    ($($product:ty),*) => {
+        $(
+            impl Successive for $product {
+                fn id(&self) -> &str {
+                    &self.id
+                }
+
+                fn related(&self) -> Option<&crate::RelatedProducts> {
+                    self.related.as_ref()
+                }
+
+                fn related_mut(&mut self) -> &mut Option<crate::RelatedProducts> {
+                    &mut self.related
+                }
+            }
+        )*
+    };
+}
+
+impl_successive!(
+    crate::CatalogProduct,
+    crate::ProducerProduct,
+    crate::ReviewProduct
+);
+
+fn graph<P: Successive>(products: &[P]) -> SuccessionGraph {
+    SuccessionGraph::new(products.iter().map(|p| (p.id(), p.related())))
+}
+
+/// Adds the missing reverse links and returns how many were added.
+fn repair<P: Successive>(products: &mut [P]) -> usize {
+    let missing = graph(products).missing_links();
+    let mut positions = HashMap::<String, Vec<usize>>::new();
+    for (position, product) in products.iter().enumerate() {
+        positions
+            .entry(product.id().to_owned())
+            .or_default()
+            .push(position);
+    }
+    for link in &missing {
+        for position in positions.get(&link.product).into_iter().flatten() {
+            let related = products[*position]
+                .related_mut()
+                .get_or_insert(crate::RelatedProducts {
+                    followed_by: None,
+                    preceded_by: None,
+                });
+            let links = match link.field {
+                LinkField::FollowedBy => &mut related.followed_by,
+                LinkField::PrecededBy => &mut related.preceded_by,
+            };
+            let links = links.get_or_insert_with(Vec::new);
+            if !links.contains(&link.target) {
+                links.push(link.target.clone());
+            }
+        }
+    }
+    missing.len()
+}
+
+impl crate::CatalogerData {
+    pub fn succession_graph(&self) -> SuccessionGraph {
+        graph(&self.products)
+    }
+
+    /// Checks that succession links are symmetric, acyclic and not branching.
+    pub fn validate_succession(&self) -> Vec<Finding> {
+        self.succession_graph().findings()
+    }
+
+    /// Adds the missing reverse succession links and returns how many were added.
+    pub fn repair_succession(&mut self) -> usize {
+        repair(&mut self.products)
+    }
+}
+
+impl crate::ProducerData {
+    pub fn succession_graph(&self) -> SuccessionGraph {
+        graph(&self.products)
+    }
+
+    /// Checks that succession links are symmetric, acyclic and not branching.
+    pub fn validate_succession(&self) -> Vec<Finding> {
+        self.succession_graph().findings()
+    }
+
+    /// Adds the missing reverse succession links and returns how many were added.
+    pub fn repair_succession(&mut self) -> usize {
+        repair(&mut self.products)
+    }
+}
+
+impl crate::ReviewerData {
+    pub fn succession_graph(&self) -> SuccessionGraph {
+        graph(&self.products)
+    }
+
+    /// Checks that succession links are symmetric, acyclic and not branching.
+    pub fn validate_succession(&self) -> Vec<Finding> {
+        self.succession_graph().findings()
+    }
+
+    /// Adds the missing reverse succession links and returns how many were added.
+    pub fn repair_succession(&mut self) -> usize {
+        repair(&mut self.products)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn related(followed_by: &[&str], preceded_by: &[&str]) -> Option<crate::RelatedProducts> {
+        let links =
+            |ids: &[&str]| (!ids.is_empty()).then(|| ids.iter().map(|id| id.to_string()).collect());
+        Some(crate::RelatedProducts {
+            followed_by: links(followed_by),
+            preceded_by: links(preceded_by),
+        })
+    }
+
+    fn product(id: &str, related: Option<crate::RelatedProducts>) -> crate::CatalogProduct {
+        crate::CatalogProduct {
+            availability: None,
+            categorisation: None,
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: None,
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: None,
+            related,
+            shopping: None,
+        }
+    }
+
+    #[test]
+    fn test_missing_links() {
+        let products = vec![
+            product("a", related(&["b"], &[])),
+            product("b", None),
+            product("c", related(&[], &["b", "x"])),
+        ];
+        assert_eq!(
+            graph(&products).missing_links(),
+            vec![
+                MissingLink {
+                    product: "b".to_owned(),
+                    field: LinkField::FollowedBy,
+                    target: "c".to_owned(),
+                },
+                MissingLink {
+                    product: "b".to_owned(),
+                    field: LinkField::PrecededBy,
+                    target: "a".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let products = vec![
+            product("c", related(&["a"], &["b"])),
+            product("a", related(&["b"], &["c"])),
+            product("b", related(&["c"], &["a"])),
+            product("d", related(&["e"], &[])),
+            product("e", related(&[], &["d"])),
+        ];
+        assert_eq!(graph(&products).cycles(), vec![vec!["a", "b", "c"]]);
+    }
+
+    #[test]
+    fn test_overlapping_cycles() {
+        let products = vec![
+            product("a", related(&["b", "c"], &[])),
+            product("b", related(&["c"], &[])),
+            product("c", related(&["a"], &[])),
+        ];
+        assert_eq!(
+            graph(&products).cycles(),
+            vec![vec!["a", "b", "c"], vec!["a", "c"]]
+        );
+
+        let products = vec![
+            product("a", related(&["b", "d"], &[])),
+            product("b", related(&["a", "c"], &[])),
+            product("c", related(&["a", "d"], &[])),
+            product("d", related(&["b"], &[])),
+        ];
+        assert_eq!(
+            graph(&products).cycles(),
+            vec![
+                vec!["a", "b"],
+                vec!["a", "b", "c"],
+                vec!["a", "d", "b"],
+                vec!["a", "d", "b", "c"],
+                vec!["b", "c", "d"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_chains() {
+        const LEN: usize = 100_000;
+        let id = |i: usize| format!("p{i:06}");
+
+        // An acyclic chain ending in a cycle of two products.
+        let mut products: Vec<_> = (0..LEN)
+            .map(|i| product(&id(i), related(&[&id(i + 1)], &[])))
+            .collect();
+        products.push(product(&id(LEN), related(&[&id(LEN - 1)], &[])));
+        assert_eq!(
+            graph(&products).cycles(),
+            vec![vec![id(LEN - 1).as_str(), id(LEN).as_str()]]
+        );
+
+        // A single cycle through all products, each followed by the previous one.
+        let products: Vec<_> = (0..LEN)
+            .map(|i| product(&id(i), related(&[&id((i + LEN - 1) % LEN)], &[])))
+            .collect();
+        let graph = graph(&products);
+        let cycles = graph.cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), LEN);
+        assert_eq!(cycles[0][..2], [id(0), id(LEN - 1)]);
+    }
+
+    #[test]
+    fn test_branching() {
+        let products = vec![
+            product("a", related(&["b", "c"], &[])),
+            product("b", related(&[], &["a"])),
+            product("c", related(&[], &["a"])),
+        ];
+        let findings = graph(&products).findings();
+        assert_eq!(
+            findings,
+            vec![Finding::new(
+                Rule::SuccessionFork,
+                EntityKind::Product,
+                "a",
+                "/related/followed_by",
+                "Followed by several products: {\"b\", \"c\"}"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_repair() {
+        let mut products = vec![
+            product("a", related(&["b"], &[])),
+            product("b", None),
+            product("c", related(&[], &["b"])),
+        ];
+        assert_eq!(repair(&mut products), 2);
+        assert_eq!(products[1].related, related(&["c"], &["a"]));
+        assert!(graph(&products).findings().is_empty());
+        assert_eq!(repair(&mut products), 0);
+    }
+}
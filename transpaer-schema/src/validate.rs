@@ -0,0 +1,1334 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::EntityKind;
+
+/// How serious a finding is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The data is wrong and should be fixed before it is used.
+    Error,
+
+    /// The data is suspicious or can be repaired automatically.
+    Warning,
+
+    /// The data is unusual but not necessarily wrong.
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+        }
+    }
+}
+
+/// The check that produced a finding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    /// The substrate does not conform to the bundled JSON schema.
+    Schema,
+
+    /// The validity window ends before it starts.
+    Validity,
+
+    /// The substrate was created in the future.
+    CreationTimestamp,
+
+    /// Invalid VAT number.
+    VatNumber,
+
+    /// Region that is not an ISO 3166-1 alpha-2 code.
+    Region,
+
+    /// Invalid EAN or GTIN code.
+    Gtin,
+
+    /// Several entities of the same kind share an `id`.
+    DuplicateId,
+
+    /// Reference to an entity not defined in the substrate.
+    UnknownReference,
+
+    /// Product listed as its own predecessor or successor.
+    SelfReference,
+
+    /// Succession link without the matching reverse link.
+    SuccessionLink,
+
+    /// Products succeeding each other in a loop.
+    SuccessionCycle,
+
+    /// Product with several successors or predecessors.
+    SuccessionFork,
+
+    /// Inconsistent review scale declared by the reviewer.
+    ReviewScale,
+
+    /// Score not fitting the scale declared by the reviewer.
+    ReviewScore,
+
+    /// Review of a different kind than the reviewer declared.
+    ReviewKind,
+}
+
+impl Rule {
+    /// Returns the stable code of the rule used in reports.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Schema => "schema",
+            Self::Validity => "validity",
+            Self::CreationTimestamp => "creation-timestamp",
+            Self::VatNumber => "vat-number",
+            Self::Region => "region",
+            Self::Gtin => "gtin",
+            Self::DuplicateId => "duplicate-id",
+            Self::UnknownReference => "unknown-reference",
+            Self::SelfReference => "self-reference",
+            Self::SuccessionLink => "succession-link",
+            Self::SuccessionCycle => "succession-cycle",
+            Self::SuccessionFork => "succession-fork",
+            Self::ReviewScale => "review-scale",
+            Self::ReviewScore => "review-score",
+            Self::ReviewKind => "review-kind",
+        }
+    }
+
+    /// Returns the severity of findings produced by the rule.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::CreationTimestamp | Self::SuccessionLink | Self::ReviewKind => Severity::Warning,
+            Self::SuccessionFork => Severity::Info,
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// A single problem found in the data.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// How serious the problem is.
+    pub severity: Severity,
+
+    /// The check that found the problem.
+    pub rule: Rule,
+
+    /// Path of the substrate file, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub substrate: Option<std::path::PathBuf>,
+
+    /// Kind of the offending entity.
+    pub entity: EntityKind,
+
+    /// `id` of the offending entity, `None` for the substrate metadata.
+    pub id: Option<String>,
+
+    /// JSON pointer to the offending value relative to the entity, e.g. `/ids/vat/0`.
+    pub path: String,
+
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl Finding {
+    pub fn new(
+        rule: Rule,
+        entity: EntityKind,
+        id: impl Into<String>,
+        path: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity: rule.severity(),
+            rule,
+            substrate: None,
+            entity,
+            id: Some(id.into()),
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates a finding about the substrate metadata.
+    pub fn meta(rule: Rule, path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: rule.severity(),
+            rule,
+            substrate: None,
+            entity: EntityKind::Meta,
+            id: None,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}] ", self.severity.as_str(), self.rule.code())?;
+        if let Some(substrate) = &self.substrate {
+            write!(f, "{}: ", substrate.display())?;
+        }
+        match &self.id {
+            Some(id) => write!(f, "{} {id:?}", self.entity.as_str())?,
+            None => write!(f, "{}", self.entity.as_str())?,
+        }
+        write!(f, " at {}: {}", self.path, self.message)
+    }
+}
+
+/// Findings of all the checks run on one or more substrates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Counts the findings of the given severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// Attributes all findings not yet attributed to a substrate to the given one.
+    pub fn with_substrate(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        let path = path.into();
+        for finding in &mut self.findings {
+            finding.substrate.get_or_insert_with(|| path.clone());
+        }
+        self
+    }
+}
+
+impl Extend<Finding> for ValidationReport {
+    fn extend<I: IntoIterator<Item = Finding>>(&mut self, iter: I) {
+        self.findings.extend(iter);
+    }
+}
+
+impl FromIterator<Finding> for ValidationReport {
+    fn from_iter<I: IntoIterator<Item = Finding>>(iter: I) -> Self {
+        Self {
+            findings: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl std::ops::AddAssign for ValidationReport {
+    fn add_assign(&mut self, other: Self) {
+        self.findings.extend(other.findings);
+    }
+}
+
+impl std::fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for finding in &self.findings {
+            writeln!(f, "{finding}")?;
+        }
+        write!(
+            f,
+            "{} errors, {} warnings, {} infos",
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.count(Severity::Info)
+        )
+    }
+}
+
+fn check_vat_numbers(
+    ids: &crate::ProducerIds,
+    entity: EntityKind,
+    id: &str,
+    findings: &mut Vec<Finding>,
+) {
+    for (i, vat) in ids.vat.iter().flatten().enumerate() {
+        if let Err(err) = crate::VatNumber::parse(vat) {
+            findings.push(Finding::new(
+                Rule::VatNumber,
+                entity,
+                id,
+                format!("/ids/vat/{i}"),
+                format!("Invalid VAT number {vat:?}: {err}"),
+            ));
+        }
+    }
+}
+
+fn check_codes(ids: &crate::ProductIds, id: &str, findings: &mut Vec<Finding>) {
+    for (field, codes) in [("ean", &ids.ean), ("gtin", &ids.gtin)] {
+        for (i, code) in codes.iter().flatten().enumerate() {
+            if let Err(err) = crate::Gtin::parse(code) {
+                findings.push(Finding::new(
+                    Rule::Gtin,
+                    EntityKind::Product,
+                    id,
+                    format!("/ids/{field}/{i}"),
+                    format!("Invalid code {code:?}: {err}"),
+                ));
+            }
+        }
+    }
+}
+
+fn check_region_list(
+    regions: &crate::RegionList,
+    entity: EntityKind,
+    id: &str,
+    path: &str,
+    findings: &mut Vec<Finding>,
+) {
+    for (i, country) in regions.countries().enumerate() {
+        if let Err(err) = country {
+            findings.push(Finding::new(
+                Rule::Region,
+                entity,
+                id,
+                format!("{path}/{i}"),
+                err.to_string(),
+            ));
+        }
+    }
+}
+
+fn check_product_regions(
+    availability: &Option<crate::ProductAvailability>,
+    origins: &Option<crate::ProductOrigins>,
+    id: &str,
+    findings: &mut Vec<Finding>,
+) {
+    if let Some(regions) = availability.as_ref().and_then(|a| a.regions.list()) {
+        check_region_list(
+            regions,
+            EntityKind::Product,
+            id,
+            "/availability/regions",
+            findings,
+        );
+    }
+    if let Some(regions) = origins.as_ref().and_then(|o| o.regions.as_ref()) {
+        check_region_list(
+            regions,
+            EntityKind::Product,
+            id,
+            "/origins/regions",
+            findings,
+        );
+    }
+}
+
+fn check_producer_regions(
+    origins: &Option<crate::ProducerOrigins>,
+    id: &str,
+    findings: &mut Vec<Finding>,
+) {
+    if let Some(regions) = origins.as_ref().and_then(|o| o.regions.as_ref()) {
+        check_region_list(
+            regions,
+            EntityKind::Producer,
+            id,
+            "/origins/regions",
+            findings,
+        );
+    }
+}
+
+/// Reports every entity whose `id` was already used by an earlier entity of the same kind.
+fn check_duplicate_ids<'a>(
+    ids: impl Iterator<Item = &'a str>,
+    entity: EntityKind,
+    findings: &mut Vec<Finding>,
+) {
+    let mut first = HashMap::new();
+    for (index, id) in ids.enumerate() {
+        if let Some(first) = first.get(id) {
+            findings.push(Finding::new(
+                Rule::DuplicateId,
+                entity,
+                id,
+                "/id",
+                format!(
+                    "Duplicate id, also used by the {} at index {first}",
+                    entity.as_str()
+                ),
+            ));
+        } else {
+            first.insert(id, index);
+        }
+    }
+}
+
+fn check_links(
+    links: &[String],
+    targets: &HashSet<&str>,
+    target: EntityKind,
+    id: &str,
+    path: &str,
+    findings: &mut Vec<Finding>,
+) {
+    for (i, link) in links.iter().enumerate() {
+        if link == id && target == EntityKind::Product {
+            findings.push(Finding::new(
+                Rule::SelfReference,
+                EntityKind::Product,
+                id,
+                format!("{path}/{i}"),
+                "Product references itself",
+            ));
+        } else if !targets.contains(link.as_str()) {
+            findings.push(Finding::new(
+                Rule::UnknownReference,
+                EntityKind::Product,
+                id,
+                format!("{path}/{i}"),
+                format!("Reference to unknown {} {link:?}", target.as_str()),
+            ));
+        }
+    }
+}
+
+fn check_product_references(
+    origins: &Option<crate::ProductOrigins>,
+    related: &Option<crate::RelatedProducts>,
+    id: &str,
+    producers: &HashSet<&str>,
+    products: &HashSet<&str>,
+    findings: &mut Vec<Finding>,
+) {
+    if let Some(origins) = origins {
+        let path = "/origins/producer_ids";
+        check_links(
+            &origins.producer_ids,
+            producers,
+            EntityKind::Producer,
+            id,
+            path,
+            findings,
+        );
+    }
+    if let Some(related) = related {
+        if let Some(followed_by) = &related.followed_by {
+            let path = "/related/followed_by";
+            check_links(
+                followed_by,
+                products,
+                EntityKind::Product,
+                id,
+                path,
+                findings,
+            );
+        }
+        if let Some(preceded_by) = &related.preceded_by {
+            let path = "/related/preceded_by";
+            check_links(
+                preceded_by,
+                products,
+                EntityKind::Product,
+                id,
+                path,
+                findings,
+            );
+        }
+    }
+}
+
+fn review_kind(review: &crate::Review) -> &'static str {
+    match review {
+        crate::Review::ScoreReview(_) => "score review",
+        crate::Review::Certification(_) => "certification",
+        crate::Review::Mention(_) => "mention",
+    }
+}
+
+/// Checks a single review against the kind of reviews the reviewer declared.
+///
+/// Mentions are accepted from every reviewer.
+fn check_review(
+    review: &Option<crate::Review>,
+    declared: &Option<crate::AboutReview>,
+    entity: EntityKind,
+    id: &str,
+    findings: &mut Vec<Finding>,
+) {
+    let Some(review) = review else {
+        return;
+    };
+    match (review, declared) {
+        (crate::Review::Mention(_), _) => {}
+        (crate::Review::ScoreReview(score), Some(crate::AboutReview::ScoreReview(scale))) => {
+            if score.value < scale.min || score.value > scale.max {
+                findings.push(Finding::new(
+                    Rule::ReviewScore,
+                    entity,
+                    id,
+                    "/review/value",
+                    format!(
+                        "Score {} outside of the declared range {}..={}",
+                        score.value, scale.min, scale.max
+                    ),
+                ));
+            }
+            if scale.div > 0 && score.value % scale.div != 0 {
+                findings.push(Finding::new(
+                    Rule::ReviewScore,
+                    entity,
+                    id,
+                    "/review/value",
+                    format!("Score {} is not a multiple of {}", score.value, scale.div),
+                ));
+            }
+        }
+        (crate::Review::Certification(_), Some(crate::AboutReview::Certification(_))) => {}
+        (review, Some(declared)) => {
+            let declared = match declared {
+                crate::AboutReview::ScoreReview(_) => "score reviews",
+                crate::AboutReview::Certification(_) => "certifications",
+            };
+            findings.push(Finding::new(
+                Rule::ReviewKind,
+                entity,
+                id,
+                "/review",
+                format!(
+                    "Found a {} but the reviewer declares {declared}",
+                    review_kind(review)
+                ),
+            ));
+        }
+        (review, None) => {
+            findings.push(Finding::new(
+                Rule::ReviewKind,
+                entity,
+                id,
+                "/review",
+                format!(
+                    "Found a {} but the reviewer declares no reviews",
+                    review_kind(review)
+                ),
+            ));
+        }
+    }
+}
+
+/// Finds the entity a schema violation in serialized provider data points into.
+///
+/// Returns the kind and `id` of the entity and the pointer relative to it.
+#[cfg(feature = "json-schema")]
+fn locate_violation(
+    pointer: &str,
+    data: &serde_json::Value,
+    about: &str,
+) -> (EntityKind, String, String) {
+    let kind = |field: &str| match field {
+        "cataloger" => EntityKind::Cataloger,
+        "producer" | "producers" => EntityKind::Producer,
+        "products" => EntityKind::Product,
+        _ => EntityKind::Reviewer,
+    };
+    let id = |entity: &serde_json::Value| entity["id"].as_str().unwrap_or_default().to_owned();
+
+    let segments: Vec<&str> = pointer.split('/').skip(1).collect();
+    match segments.as_slice() {
+        [field @ ("producers" | "products" | "reviewers"), index, rest @ ..] => {
+            let entity = index.parse().map(|i: usize| &data[field][i]);
+            let id = entity.map(id).unwrap_or_default();
+            (
+                kind(field),
+                id,
+                rest.iter().map(|s| format!("/{s}")).collect(),
+            )
+        }
+        [field @ ("cataloger" | "producer" | "reviewer"), rest @ ..] => (
+            kind(field),
+            id(&data[field]),
+            rest.iter().map(|s| format!("/{s}")).collect(),
+        ),
+        _ => (kind(about), id(&data[about]), pointer.to_owned()),
+    }
+}
+
+/// Checks the substrate against the bundled JSON schema.
+#[cfg(feature = "json-schema")]
+fn check_schema(substrate: &crate::Substrate, findings: &mut Vec<Finding>) {
+    const SERIALIZE: &str = "substrates always serialize to JSON";
+    let validator = crate::schema::validator();
+
+    let meta = serde_json::to_value(&substrate.meta).expect(SERIALIZE);
+    for violation in validator.validate("meta", &meta) {
+        findings.push(Finding::meta(
+            Rule::Schema,
+            violation.pointer,
+            violation.message,
+        ));
+    }
+
+    let (definition, about, data) = match &substrate.data {
+        crate::Data::Cataloger(data) => ("catalogerData", "cataloger", serde_json::to_value(data)),
+        crate::Data::Producer(data) => ("producerData", "producer", serde_json::to_value(data)),
+        crate::Data::Reviewer(data) => ("reviewerData", "reviewer", serde_json::to_value(data)),
+    };
+    let data = data.expect(SERIALIZE);
+    for violation in validator.validate(definition, &data) {
+        let (entity, id, path) = locate_violation(&violation.pointer, &data, about);
+        findings.push(Finding::new(
+            Rule::Schema,
+            entity,
+            id,
+            path,
+            violation.message,
+        ));
+    }
+}
+
+impl crate::Meta {
+    /// Checks that the validity window is not inverted and that the substrate was not created
+    /// after `now`.
+    pub fn validate_timestamps(&self, now: chrono::DateTime<chrono::Utc>) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        if let (Some(valid_from), Some(valid_to)) = (self.valid_from, self.valid_to) {
+            if valid_to < valid_from {
+                findings.push(Finding::meta(
+                    Rule::Validity,
+                    "/valid_to",
+                    format!("Validity ends ({valid_to}) before it starts ({valid_from})"),
+                ));
+            }
+        }
+        if let Some(creation_timestamp) = self.creation_timestamp {
+            if creation_timestamp > now {
+                findings.push(Finding::meta(
+                    Rule::CreationTimestamp,
+                    "/creation_timestamp",
+                    format!("Creation timestamp {creation_timestamp} is in the future"),
+                ));
+            }
+        }
+        findings
+    }
+}
+
+impl crate::CatalogerData {
+    /// Checks the VAT numbers of all producers.
+    pub fn validate_vat_numbers(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for producer in &self.producers {
+            check_vat_numbers(
+                &producer.ids,
+                EntityKind::Producer,
+                &producer.id,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks that all product availability, product origin and producer origin regions are
+    /// ISO 3166-1 alpha-2 codes.
+    pub fn validate_regions(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for producer in &self.producers {
+            check_producer_regions(&producer.origins, &producer.id, &mut findings);
+        }
+        for product in &self.products {
+            check_product_regions(
+                &product.availability,
+                &product.origins,
+                &product.id,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks that `id`s are unique and that producer and product references point at
+    /// entities defined in this substrate.
+    pub fn validate_references(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        check_duplicate_ids(
+            self.producers.iter().map(|p| p.id.as_str()),
+            EntityKind::Producer,
+            &mut findings,
+        );
+        check_duplicate_ids(
+            self.products.iter().map(|p| p.id.as_str()),
+            EntityKind::Product,
+            &mut findings,
+        );
+        let producers = self.producers.iter().map(|p| p.id.as_str()).collect();
+        let products = self.products.iter().map(|p| p.id.as_str()).collect();
+        for product in &self.products {
+            check_product_references(
+                &product.origins,
+                &product.related,
+                &product.id,
+                &producers,
+                &products,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks the EAN and GTIN codes of all products.
+    pub fn validate_codes(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for product in &self.products {
+            check_codes(&product.ids, &product.id, &mut findings);
+        }
+        findings
+    }
+
+    /// Runs all checks that need nothing but the data itself.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        report.extend(self.validate_vat_numbers());
+        report.extend(self.validate_regions());
+        report.extend(self.validate_codes());
+        report.extend(self.validate_references());
+        report.extend(self.validate_succession());
+        report
+    }
+}
+
+impl crate::ProducerData {
+    /// Checks the VAT numbers of the producer.
+    pub fn validate_vat_numbers(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let producer = &self.producer;
+        check_vat_numbers(
+            &producer.ids,
+            EntityKind::Producer,
+            &producer.id,
+            &mut findings,
+        );
+        findings
+    }
+
+    /// Checks that all product availability, product origin and producer origin regions are
+    /// ISO 3166-1 alpha-2 codes.
+    pub fn validate_regions(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        check_producer_regions(&self.producer.origins, &self.producer.id, &mut findings);
+        for product in &self.products {
+            check_product_regions(
+                &product.availability,
+                &product.origins,
+                &product.id,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks that `id`s are unique and that producer and product references point at
+    /// entities defined in this substrate.
+    pub fn validate_references(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        check_duplicate_ids(
+            self.products.iter().map(|p| p.id.as_str()),
+            EntityKind::Product,
+            &mut findings,
+        );
+        check_duplicate_ids(
+            self.reviewers.iter().map(|r| r.id.as_str()),
+            EntityKind::Reviewer,
+            &mut findings,
+        );
+        let producers = HashSet::from([self.producer.id.as_str()]);
+        let products = self.products.iter().map(|p| p.id.as_str()).collect();
+        for product in &self.products {
+            check_product_references(
+                &product.origins,
+                &product.related,
+                &product.id,
+                &producers,
+                &products,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks the EAN and GTIN codes of all products.
+    pub fn validate_codes(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for product in &self.products {
+            check_codes(&product.ids, &product.id, &mut findings);
+        }
+        findings
+    }
+
+    /// Runs all checks that need nothing but the data itself.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        report.extend(self.validate_vat_numbers());
+        report.extend(self.validate_regions());
+        report.extend(self.validate_codes());
+        report.extend(self.validate_references());
+        report.extend(self.validate_succession());
+        report
+    }
+}
+
+impl crate::ReviewerData {
+    /// Checks the VAT numbers of all producers.
+    pub fn validate_vat_numbers(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for producer in &self.producers {
+            check_vat_numbers(
+                &producer.ids,
+                EntityKind::Producer,
+                &producer.id,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks that all product availability, product origin and producer origin regions are
+    /// ISO 3166-1 alpha-2 codes.
+    pub fn validate_regions(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for producer in &self.producers {
+            check_producer_regions(&producer.origins, &producer.id, &mut findings);
+        }
+        for product in &self.products {
+            check_product_regions(
+                &product.availability,
+                &product.origins,
+                &product.id,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks that `id`s are unique and that producer and product references point at
+    /// entities defined in this substrate.
+    pub fn validate_references(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        check_duplicate_ids(
+            self.producers.iter().map(|p| p.id.as_str()),
+            EntityKind::Producer,
+            &mut findings,
+        );
+        check_duplicate_ids(
+            self.products.iter().map(|p| p.id.as_str()),
+            EntityKind::Product,
+            &mut findings,
+        );
+        let producers = self.producers.iter().map(|p| p.id.as_str()).collect();
+        let products = self.products.iter().map(|p| p.id.as_str()).collect();
+        for product in &self.products {
+            check_product_references(
+                &product.origins,
+                &product.related,
+                &product.id,
+                &producers,
+                &products,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks that the reviews match the kind and scale the reviewer declared.
+    pub fn validate_reviews(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let reviewer = &self.reviewer;
+        if let Some(crate::AboutReview::ScoreReview(scale)) = &reviewer.reviews {
+            if scale.min > scale.max {
+                findings.push(Finding::new(
+                    Rule::ReviewScale,
+                    EntityKind::Reviewer,
+                    &reviewer.id,
+                    "/reviews",
+                    format!(
+                        "Minimum {} is greater than maximum {}",
+                        scale.min, scale.max
+                    ),
+                ));
+            }
+            if scale.div <= 0 {
+                findings.push(Finding::new(
+                    Rule::ReviewScale,
+                    EntityKind::Reviewer,
+                    &reviewer.id,
+                    "/reviews/div",
+                    format!("Divisor {} is not positive", scale.div),
+                ));
+            }
+        }
+        for producer in &self.producers {
+            let id = &producer.id;
+            check_review(
+                &producer.review,
+                &reviewer.reviews,
+                EntityKind::Producer,
+                id,
+                &mut findings,
+            );
+        }
+        for product in &self.products {
+            let id = &product.id;
+            check_review(
+                &product.review,
+                &reviewer.reviews,
+                EntityKind::Product,
+                id,
+                &mut findings,
+            );
+        }
+        findings
+    }
+
+    /// Checks the EAN and GTIN codes of all products.
+    pub fn validate_codes(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for product in &self.products {
+            check_codes(&product.ids, &product.id, &mut findings);
+        }
+        findings
+    }
+
+    /// Runs all checks that need nothing but the data itself.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        report.extend(self.validate_vat_numbers());
+        report.extend(self.validate_regions());
+        report.extend(self.validate_codes());
+        report.extend(self.validate_references());
+        report.extend(self.validate_succession());
+        report.extend(self.validate_reviews());
+        report
+    }
+}
+
+impl crate::Data {
+    pub fn validate(&self) -> ValidationReport {
+        match self {
+            Self::Cataloger(data) => data.validate(),
+            Self::Producer(data) => data.validate(),
+            Self::Reviewer(data) => data.validate(),
+        }
+    }
+}
+
+impl crate::Substrate {
+    /// Runs all checks on the metadata and the data.
+    ///
+    /// With the `json-schema` feature the substrate is also checked against the bundled schema.
+    pub fn validate(&self) -> ValidationReport {
+        self.validate_at(chrono::Utc::now())
+    }
+
+    /// Same as `validate` but checks timestamps against `now` instead of the current time.
+    pub fn validate_at(&self, now: chrono::DateTime<chrono::Utc>) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        #[cfg(feature = "json-schema")]
+        check_schema(self, &mut report.findings);
+        report.extend(self.meta.validate_timestamps(now));
+        report += self.data.validate();
+        report
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn producer(id: &str, vat: &[&str]) -> crate::CatalogProducer {
+        crate::CatalogProducer {
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProducerIds {
+                domains: None,
+                vat: Some(vat.iter().map(|v| v.to_string()).collect()),
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: None,
+            websites: Vec::new(),
+        }
+    }
+
+    fn product(id: &str) -> crate::CatalogProduct {
+        crate::CatalogProduct {
+            availability: None,
+            categorisation: None,
+            description: None,
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: None,
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: None,
+            related: None,
+            shopping: None,
+        }
+    }
+
+    fn cataloger_data(
+        producers: Vec<crate::CatalogProducer>,
+        products: Vec<crate::CatalogProduct>,
+    ) -> crate::CatalogerData {
+        crate::CatalogerData {
+            cataloger: crate::AboutCataloger {
+                description: None,
+                id: "cataloger".to_owned(),
+                name: "Cataloger".to_owned(),
+                variant: crate::CatalogVariant::Database,
+                website: "https://example.com".to_owned(),
+            },
+            producers,
+            products,
+        }
+    }
+
+    #[test]
+    fn test_validate_vat_numbers() {
+        let data = cataloger_data(
+            vec![
+                producer("good", &["DE136695976"]),
+                producer("bad", &["NL004495445B01", "136695976"]),
+            ],
+            Vec::new(),
+        );
+
+        assert_eq!(
+            data.validate_vat_numbers(),
+            vec![Finding::new(
+                Rule::VatNumber,
+                EntityKind::Producer,
+                "bad",
+                "/ids/vat/1",
+                "Invalid VAT number \"136695976\": Missing country prefix"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_regions() {
+        let mut producer = producer("producer", &[]);
+        producer.origins = Some(crate::ProducerOrigins {
+            regions: Some(crate::RegionList(vec![
+                "NL".to_owned(),
+                "Germany".to_owned(),
+            ])),
+        });
+        let mut available = product("available");
+        available.availability = Some(crate::ProductAvailability {
+            regions: crate::Regions::List(crate::RegionList(vec!["UK".to_owned()])),
+        });
+        let mut everywhere = product("everywhere");
+        everywhere.availability = Some(crate::ProductAvailability {
+            regions: crate::Regions::Variant(crate::RegionVariant::All),
+        });
+        everywhere.origins = Some(crate::ProductOrigins {
+            producer_ids: Vec::new(),
+            regions: Some(crate::RegionList(vec!["XX".to_owned()])),
+        });
+        let data = cataloger_data(vec![producer], vec![available, everywhere]);
+
+        let paths: Vec<_> = data
+            .validate_regions()
+            .into_iter()
+            .map(|f| (f.id.unwrap_or_default(), f.path))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("producer".to_owned(), "/origins/regions/1".to_owned()),
+                ("available".to_owned(), "/availability/regions/0".to_owned()),
+                ("everywhere".to_owned(), "/origins/regions/0".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_references() {
+        let mut first = product("first");
+        first.origins = Some(crate::ProductOrigins {
+            producer_ids: vec!["producer".to_owned(), "ghost".to_owned()],
+            regions: None,
+        });
+        first.related = Some(crate::RelatedProducts {
+            followed_by: Some(vec!["second".to_owned(), "first".to_owned()]),
+            preceded_by: Some(vec!["zeroth".to_owned()]),
+        });
+        let data = cataloger_data(
+            vec![producer("producer", &[])],
+            vec![first, product("second"), product("second")],
+        );
+
+        assert_eq!(
+            data.validate_references(),
+            vec![
+                Finding::new(
+                    Rule::DuplicateId,
+                    EntityKind::Product,
+                    "second",
+                    "/id",
+                    "Duplicate id, also used by the product at index 1"
+                ),
+                Finding::new(
+                    Rule::UnknownReference,
+                    EntityKind::Product,
+                    "first",
+                    "/origins/producer_ids/1",
+                    "Reference to unknown producer \"ghost\""
+                ),
+                Finding::new(
+                    Rule::SelfReference,
+                    EntityKind::Product,
+                    "first",
+                    "/related/followed_by/1",
+                    "Product references itself"
+                ),
+                Finding::new(
+                    Rule::UnknownReference,
+                    EntityKind::Product,
+                    "first",
+                    "/related/preceded_by/0",
+                    "Reference to unknown product \"zeroth\""
+                ),
+            ]
+        );
+    }
+
+    fn review_product(id: &str, review: crate::Review) -> crate::ReviewProduct {
+        crate::ReviewProduct {
+            availability: None,
+            categorisation: None,
+            id: id.to_owned(),
+            ids: crate::ProductIds {
+                ean: None,
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: None,
+            related: None,
+            reports: None,
+            review: Some(review),
+            shopping: None,
+            summary: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_reviews() {
+        let score = |value| crate::Review::ScoreReview(crate::ScoreReview { value });
+        let data = crate::ReviewerData {
+            producers: Vec::new(),
+            products: vec![
+                review_product("good", score(50)),
+                review_product("high", score(110)),
+                review_product("odd", score(55)),
+                review_product(
+                    "certified",
+                    crate::Review::Certification(crate::Certification {
+                        is_certified: Some(true),
+                    }),
+                ),
+                review_product(
+                    "mentioned",
+                    crate::Review::Mention(crate::Mention {
+                        title: "Title".to_owned(),
+                        url: "https://example.com".to_owned(),
+                    }),
+                ),
+            ],
+            reviewer: crate::AboutReviewer {
+                description: "Reviewer".to_owned(),
+                id: "reviewer".to_owned(),
+                name: "Reviewer".to_owned(),
+                reviews: Some(crate::AboutReview::ScoreReview(crate::AboutScoreReview {
+                    div: 10,
+                    max: 100,
+                    min: 0,
+                })),
+                website: "https://example.com".to_owned(),
+            },
+        };
+
+        assert_eq!(
+            data.validate_reviews(),
+            vec![
+                Finding::new(
+                    Rule::ReviewScore,
+                    EntityKind::Product,
+                    "high",
+                    "/review/value",
+                    "Score 110 outside of the declared range 0..=100"
+                ),
+                Finding::new(
+                    Rule::ReviewScore,
+                    EntityKind::Product,
+                    "odd",
+                    "/review/value",
+                    "Score 55 is not a multiple of 10"
+                ),
+                Finding::new(
+                    Rule::ReviewKind,
+                    EntityKind::Product,
+                    "certified",
+                    "/review",
+                    "Found a certification but the reviewer declares score reviews"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_timestamps() {
+        let at = |day| {
+            chrono::NaiveDate::from_ymd_opt(2025, 1, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let mut meta = crate::Meta {
+            authors: Vec::new(),
+            creation_timestamp: Some(at(3)),
+            description: None,
+            title: "Title".to_owned(),
+            valid_from: Some(at(2)),
+            valid_to: Some(at(4)),
+            variant: crate::ProviderVariant::Cataloger,
+            version: "1".to_owned(),
+        };
+        assert_eq!(meta.validate_timestamps(at(3)), Vec::new());
+
+        meta.valid_from = Some(at(5));
+        assert_eq!(
+            meta.validate_timestamps(at(1)),
+            vec![
+                Finding::meta(
+                    Rule::Validity,
+                    "/valid_to",
+                    "Validity ends (2025-01-04 00:00:00 UTC) before it starts (2025-01-05 00:00:00 UTC)"
+                ),
+                Finding::meta(
+                    Rule::CreationTimestamp,
+                    "/creation_timestamp",
+                    "Creation timestamp 2025-01-03 00:00:00 UTC is in the future"
+                ),
+            ]
+        );
+    }
+
+    fn substrate(data: crate::CatalogerData) -> crate::Substrate {
+        crate::Substrate {
+            meta: crate::Meta {
+                authors: Vec::new(),
+                creation_timestamp: None,
+                description: None,
+                title: "Title".to_owned(),
+                valid_from: None,
+                valid_to: None,
+                variant: crate::ProviderVariant::Cataloger,
+                version: "1".to_owned(),
+            },
+            data: crate::Data::Cataloger(data),
+        }
+    }
+
+    #[test]
+    fn test_validation_report() {
+        let mut first = product("first");
+        first.ids.ean = Some(vec!["4006381333931".to_owned(), "4006381333932".to_owned()]);
+        first.related = Some(crate::RelatedProducts {
+            followed_by: Some(vec!["second".to_owned()]),
+            preceded_by: None,
+        });
+        let data = cataloger_data(
+            vec![producer("producer", &["XX123"])],
+            vec![first, product("second")],
+        );
+
+        let report = substrate(data).validate().with_substrate("catalog.yaml");
+        let rules: Vec<_> = report.findings.iter().map(|f| f.rule).collect();
+        assert_eq!(
+            rules,
+            vec![Rule::VatNumber, Rule::Gtin, Rule::SuccessionLink]
+        );
+        assert!(report.has_errors());
+        assert_eq!(report.count(Severity::Warning), 1);
+
+        assert_eq!(
+            report.to_string().lines().nth(1),
+            Some(
+                "error[gtin] catalog.yaml: product \"first\" at /ids/ean/1: \
+                 Invalid code \"4006381333932\": Wrong check digit: expected 1, found 2"
+            )
+        );
+        assert_eq!(
+            report.to_string().lines().last(),
+            Some("2 errors, 1 warnings, 0 infos")
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json["findings"][2],
+            serde_json::json!({
+                "severity": "warning",
+                "rule": "succession-link",
+                "substrate": "catalog.yaml",
+                "entity": "product",
+                "id": "second",
+                "path": "/related/preceded_by",
+                "message": "Missing reverse link to \"first\"",
+            })
+        );
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[test]
+    fn test_validate_schema() {
+        let mut first = product("first");
+        first.ids.wiki = Some(vec!["Q1".to_owned(), "Q1".to_owned()]);
+        let report =
+            substrate(cataloger_data(Vec::new(), vec![product("zeroth"), first])).validate();
+
+        let findings: Vec<_> = report
+            .findings
+            .iter()
+            .map(|f| (f.rule, f.entity, f.id.as_deref(), f.path.as_str()))
+            .collect();
+        assert_eq!(
+            findings,
+            vec![(
+                Rule::Schema,
+                EntityKind::Product,
+                Some("first"),
+                "/ids/wiki"
+            )]
+        );
+    }
+}
@@ -0,0 +1,731 @@
+use snafu::prelude::*;
+
+use crate::errors;
+
+/// Format of a VAT number for one country.
+///
+/// Patterns describe the part after the country prefix. Lowercase letters are character classes:
+/// `d` is a digit, `l` a letter, `x` a digit or a letter and `c` a letter, `+` or `*`.
+/// All other characters must match literally.
+struct Rule {
+    prefix: &'static str,
+    patterns: &'static [&'static str],
+    checksum: Option<fn(&str) -> bool>,
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        prefix: "AT",
+        patterns: &["Udddddddd"],
+        checksum: Some(check_at),
+    },
+    Rule {
+        prefix: "BE",
+        patterns: &["dddddddddd"],
+        checksum: Some(check_be),
+    },
+    Rule {
+        prefix: "BG",
+        patterns: &["ddddddddd", "dddddddddd"],
+        checksum: Some(check_bg),
+    },
+    Rule {
+        prefix: "CY",
+        patterns: &["ddddddddl"],
+        checksum: Some(check_cy),
+    },
+    Rule {
+        prefix: "CZ",
+        patterns: &["dddddddd", "ddddddddd", "dddddddddd"],
+        checksum: Some(check_cz),
+    },
+    Rule {
+        prefix: "DE",
+        patterns: &["ddddddddd"],
+        checksum: Some(check_iso7064_mod11_10),
+    },
+    Rule {
+        prefix: "DK",
+        patterns: &["dddddddd"],
+        checksum: Some(check_dk),
+    },
+    Rule {
+        prefix: "EE",
+        patterns: &["ddddddddd"],
+        checksum: Some(check_ee),
+    },
+    Rule {
+        prefix: "EL",
+        patterns: &["ddddddddd"],
+        checksum: Some(check_el),
+    },
+    Rule {
+        prefix: "ES",
+        patterns: &["xdddddddx"],
+        checksum: Some(check_es),
+    },
+    Rule {
+        prefix: "FI",
+        patterns: &["dddddddd"],
+        checksum: Some(check_fi),
+    },
+    Rule {
+        prefix: "FR",
+        patterns: &["xxddddddddd"],
+        checksum: Some(check_fr),
+    },
+    Rule {
+        prefix: "HR",
+        patterns: &["ddddddddddd"],
+        checksum: Some(check_iso7064_mod11_10),
+    },
+    Rule {
+        prefix: "HU",
+        patterns: &["dddddddd"],
+        checksum: Some(check_hu),
+    },
+    Rule {
+        prefix: "IE",
+        patterns: &["dddddddl", "dddddddll", "dcdddddl"],
+        checksum: Some(check_ie),
+    },
+    Rule {
+        prefix: "IT",
+        patterns: &["ddddddddddd"],
+        checksum: Some(check_luhn),
+    },
+    Rule {
+        prefix: "LT",
+        patterns: &["ddddddddd", "dddddddddddd"],
+        checksum: Some(check_lt),
+    },
+    Rule {
+        prefix: "LU",
+        patterns: &["dddddddd"],
+        checksum: Some(check_lu),
+    },
+    Rule {
+        prefix: "LV",
+        patterns: &["ddddddddddd"],
+        checksum: Some(check_lv),
+    },
+    Rule {
+        prefix: "MT",
+        patterns: &["dddddddd"],
+        checksum: Some(check_mt),
+    },
+    Rule {
+        prefix: "NL",
+        patterns: &["dddddddddBdd"],
+        checksum: Some(check_nl),
+    },
+    Rule {
+        prefix: "PL",
+        patterns: &["dddddddddd"],
+        checksum: Some(check_pl),
+    },
+    Rule {
+        prefix: "PT",
+        patterns: &["ddddddddd"],
+        checksum: Some(check_pt),
+    },
+    Rule {
+        prefix: "RO",
+        patterns: &[
+            "dd",
+            "ddd",
+            "dddd",
+            "ddddd",
+            "dddddd",
+            "ddddddd",
+            "dddddddd",
+            "ddddddddd",
+            "dddddddddd",
+        ],
+        checksum: Some(check_ro),
+    },
+    Rule {
+        prefix: "SE",
+        patterns: &["dddddddddd01"],
+        checksum: Some(check_se),
+    },
+    Rule {
+        prefix: "SI",
+        patterns: &["dddddddd"],
+        checksum: Some(check_si),
+    },
+    Rule {
+        prefix: "SK",
+        patterns: &["dddddddddd"],
+        checksum: Some(check_sk),
+    },
+    Rule {
+        prefix: "XI",
+        patterns: &["ddddddddd", "dddddddddddd", "GDddd", "HAddd"],
+        checksum: Some(check_gb),
+    },
+    Rule {
+        prefix: "GB",
+        patterns: &["ddddddddd", "dddddddddddd", "GDddd", "HAddd"],
+        checksum: Some(check_gb),
+    },
+    Rule {
+        prefix: "CHE",
+        patterns: &["ddddddddd"],
+        checksum: Some(check_ch),
+    },
+    Rule {
+        prefix: "NO",
+        patterns: &["ddddddddd", "dddddddddMVA"],
+        checksum: Some(check_no),
+    },
+];
+
+/// Suffixes Swiss numbers carry to mark the language of the VAT register.
+const CH_SUFFIXES: &[&str] = &["MWST", "TVA", "IVA"];
+
+/// A VAT identifier that passed the offline format and checksum checks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VatNumber {
+    /// Country prefix, e.g. `DE`, `EL` or `CHE`.
+    pub prefix: String,
+
+    /// The number without the prefix, spaces and punctuation.
+    pub number: String,
+}
+
+impl VatNumber {
+    /// Checks the country prefix, length, pattern and (where the algorithm is public) check digits.
+    ///
+    /// Spaces, dots and dashes are ignored and letters are case-insensitive.
+    pub fn parse(vat: &str) -> Result<Self, errors::VatError> {
+        let compact: String = vat
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '.' && *c != '-')
+            .flat_map(char::to_uppercase)
+            .collect();
+
+        let prefix_len = compact
+            .chars()
+            .take_while(char::is_ascii_alphabetic)
+            .count();
+        ensure!(prefix_len >= 2, errors::vat::MissingPrefixSnafu);
+
+        let rule = RULES
+            .iter()
+            .find(|rule| compact.starts_with(rule.prefix))
+            .context(errors::vat::UnknownCountrySnafu {
+                prefix: &compact[..2],
+            })?;
+
+        let mut number = &compact[rule.prefix.len()..];
+        if rule.prefix == "CHE" {
+            for suffix in CH_SUFFIXES {
+                number = number.strip_suffix(suffix).unwrap_or(number);
+            }
+        }
+
+        ensure!(
+            rule.patterns
+                .iter()
+                .any(|pattern| matches_pattern(number, pattern)),
+            errors::vat::FormatSnafu {
+                prefix: rule.prefix,
+                expected: rule.patterns.join(" or ")
+            }
+        );
+
+        if let Some(checksum) = rule.checksum {
+            ensure!(
+                checksum(number),
+                errors::vat::ChecksumSnafu {
+                    prefix: rule.prefix
+                }
+            );
+        }
+
+        Ok(Self {
+            prefix: rule.prefix.to_owned(),
+            number: number.to_owned(),
+        })
+    }
+
+    /// Checks if the VAT number passes all offline checks.
+    pub fn is_valid(vat: &str) -> bool {
+        Self::parse(vat).is_ok()
+    }
+}
+
+impl std::str::FromStr for VatNumber {
+    type Err = errors::VatError;
+
+    fn from_str(vat: &str) -> Result<Self, Self::Err> {
+        Self::parse(vat)
+    }
+}
+
+impl std::fmt::Display for VatNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.prefix, self.number)
+    }
+}
+
+fn matches_pattern(number: &str, pattern: &str) -> bool {
+    number.len() == pattern.len()
+        && number.chars().zip(pattern.chars()).all(|(n, p)| match p {
+            'd' => n.is_ascii_digit(),
+            'l' => n.is_ascii_uppercase(),
+            'x' => n.is_ascii_digit() || n.is_ascii_uppercase(),
+            'c' => n.is_ascii_uppercase() || n == '+' || n == '*',
+            _ => n == p,
+        })
+}
+
+/// Returns the decimal digits of the string, skipping all other characters.
+fn digits(number: &str) -> Vec<u32> {
+    number.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn weighted_sum(digits: &[u32], weights: &[u32]) -> u32 {
+    digits.iter().zip(weights).map(|(d, w)| d * w).sum()
+}
+
+fn check_luhn(number: &str) -> bool {
+    let sum: u32 = digits(number)
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| {
+            if i % 2 == 1 {
+                (d * 2) / 10 + (d * 2) % 10
+            } else {
+                *d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn check_iso7064_mod11_10(number: &str) -> bool {
+    let digits = digits(number);
+    let (check, payload) = digits.split_last().expect("pattern checked");
+    let mut product = 10;
+    for digit in payload {
+        let mut sum = (digit + product) % 10;
+        if sum == 0 {
+            sum = 10;
+        }
+        product = (2 * sum) % 11;
+    }
+    (11 - product) % 10 == *check
+}
+
+fn check_at(number: &str) -> bool {
+    let digits = digits(number);
+    let sum: u32 = digits[..7]
+        .iter()
+        .enumerate()
+        .map(|(i, d)| {
+            if i % 2 == 1 {
+                (d * 2) / 10 + (d * 2) % 10
+            } else {
+                *d
+            }
+        })
+        .sum();
+    (10 - (sum + 4) % 10) % 10 == digits[7]
+}
+
+fn check_be(number: &str) -> bool {
+    let (base, check) = number.split_at(8);
+    let base: u64 = base.parse().expect("pattern checked");
+    let check: u64 = check.parse().expect("pattern checked");
+    97 - base % 97 == check
+}
+
+/// Nine digits are legal entities, ten digits are personal numbers of citizens or foreigners
+/// or numbers of other taxpayers, each with their own check digit.
+fn check_bg(number: &str) -> bool {
+    let digits = digits(number);
+    let (check, payload) = digits.split_last().expect("pattern checked");
+    if payload.len() == 8 {
+        let mut sum = weighted_sum(payload, &[1, 2, 3, 4, 5, 6, 7, 8]) % 11;
+        if sum == 10 {
+            sum = weighted_sum(payload, &[3, 4, 5, 6, 7, 8, 9, 10]) % 11;
+        }
+        return sum % 10 == *check;
+    }
+    let citizen = weighted_sum(payload, &[2, 4, 8, 5, 10, 9, 7, 3, 6]) % 11 % 10;
+    let foreigner = weighted_sum(payload, &[21, 19, 17, 13, 11, 9, 7, 3, 1]) % 10;
+    let other = (11 - weighted_sum(payload, &[4, 3, 2, 7, 6, 5, 4, 3, 2]) % 11) % 11;
+    [citizen, foreigner, other].contains(check)
+}
+
+fn check_cy(number: &str) -> bool {
+    const EVEN: [u32; 10] = [1, 0, 5, 7, 9, 13, 15, 17, 19, 21];
+    let sum: u32 = digits(number)
+        .iter()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { EVEN[*d as usize] } else { *d })
+        .sum();
+    let check = char::from(b'A' + (sum % 26) as u8);
+    !number.starts_with("12") && number.ends_with(check)
+}
+
+/// Eight digits are legal entities, nine digits starting with 6 are special cases and other
+/// numbers are birth numbers, which have no check digit if issued before 1954.
+fn check_cz(number: &str) -> bool {
+    let digits = digits(number);
+    match digits.len() {
+        8 => {
+            let sum = weighted_sum(&digits, &[8, 7, 6, 5, 4, 3, 2]);
+            let check = match (11 - sum % 11) % 11 {
+                0 => 1,
+                check => check,
+            };
+            digits[0] != 9 && check % 10 == digits[7]
+        }
+        9 if digits[0] == 6 => {
+            let sum = weighted_sum(&digits[1..], &[8, 7, 6, 5, 4, 3, 2]) % 11;
+            9 - (11 - sum) % 10 == digits[8]
+        }
+        9 => true,
+        _ => {
+            let number: u64 = number.parse().expect("pattern checked");
+            number.is_multiple_of(11) || (number / 10 % 11 == 10 && digits[9] == 0)
+        }
+    }
+}
+
+fn check_dk(number: &str) -> bool {
+    weighted_sum(&digits(number), &[2, 7, 6, 5, 4, 3, 2, 1]).is_multiple_of(11)
+}
+
+fn check_ee(number: &str) -> bool {
+    let digits = digits(number);
+    (10 - weighted_sum(&digits, &[3, 7, 1, 3, 7, 1, 3, 7]) % 10) % 10 == digits[8]
+}
+
+fn check_el(number: &str) -> bool {
+    let digits = digits(number);
+    weighted_sum(&digits, &[256, 128, 64, 32, 16, 8, 4, 2]) % 11 % 10 == digits[8]
+}
+
+/// Personal numbers (DNI and NIE) end with a check letter, company numbers (CIF) with a check
+/// digit or an equivalent letter.
+fn check_es(number: &str) -> bool {
+    const LETTERS: &[u8] = b"TRWAGMYFPDXBNJZSQVHLCKE";
+    let dni = |digits: &str| {
+        let digits: u32 = digits.parse().expect("pattern checked");
+        number.ends_with(char::from(LETTERS[(digits % 23) as usize]))
+    };
+    match number.as_bytes()[0] {
+        b'0'..=b'9' => dni(&number[..8]),
+        b'K' | b'L' | b'M' => dni(&number[1..8]),
+        first @ (b'X' | b'Y' | b'Z') => dni(&format!("{}{}", first - b'X', &number[1..8])),
+        _ => {
+            let check = (0..10)
+                .find(|check| check_luhn(&format!("{}{check}", &number[1..8])))
+                .expect("one check digit always passes");
+            let letter = char::from(b"JABCDEFGHI"[check as usize]);
+            let digit = char::from_digit(check, 10).expect("single digit");
+            number.ends_with(letter) || number.ends_with(digit)
+        }
+    }
+}
+
+fn check_fi(number: &str) -> bool {
+    let digits = digits(number);
+    match weighted_sum(&digits, &[7, 9, 10, 5, 8, 4, 2]) % 11 {
+        0 => digits[7] == 0,
+        1 => false,
+        rest => 11 - rest == digits[7],
+    }
+}
+
+/// Only numeric keys have a public algorithm, alphanumeric ones are accepted as they are.
+fn check_fr(number: &str) -> bool {
+    let (key, siren) = number.split_at(2);
+    match key.parse::<u64>() {
+        Ok(key) => {
+            let siren: u64 = siren.parse().expect("pattern checked");
+            key == (12 + 3 * (siren % 97)) % 97
+        }
+        Err(_) => true,
+    }
+}
+
+fn check_hu(number: &str) -> bool {
+    let digits = digits(number);
+    (10 - weighted_sum(&digits, &[9, 7, 3, 1, 9, 7, 3]) % 10) % 10 == digits[7]
+}
+
+/// Numbers in the old format, with a letter or symbol second, are rearranged into the new one.
+fn check_ie(number: &str) -> bool {
+    const ALPHABET: &str = "WABCDEFGHIJKLMNOPQRSTUV";
+    let number = if number.as_bytes()[1].is_ascii_digit() {
+        number.to_owned()
+    } else {
+        format!("0{}{}{}", &number[2..7], &number[..1], &number[7..])
+    };
+    let suffix = number[8..].chars().next();
+    let suffix = suffix.and_then(|c| ALPHABET.find(c)).unwrap_or(0) as u32;
+    let sum = weighted_sum(&digits(&number[..7]), &[8, 7, 6, 5, 4, 3, 2]) + 9 * suffix;
+    ALPHABET.chars().nth((sum % 23) as usize) == number[7..].chars().next()
+}
+
+fn check_lt(number: &str) -> bool {
+    let digits = digits(number);
+    let (check, payload) = digits.split_last().expect("pattern checked");
+    let weights = |offset: u32| -> Vec<u32> {
+        (0..payload.len() as u32)
+            .map(|i| 1 + (i + offset) % 9)
+            .collect()
+    };
+    let mut sum = weighted_sum(payload, &weights(0)) % 11;
+    if sum == 10 {
+        sum = weighted_sum(payload, &weights(2)) % 11;
+    }
+    payload[payload.len() - 1] == 1 && sum % 10 == *check
+}
+
+fn check_lu(number: &str) -> bool {
+    let (base, check) = number.split_at(6);
+    let base: u64 = base.parse().expect("pattern checked");
+    let check: u64 = check.parse().expect("pattern checked");
+    base % 89 == check
+}
+
+/// Legal entities start with a digit above 3, personal codes issued since 2017 start with 32 and
+/// have no check digit.
+fn check_lv(number: &str) -> bool {
+    let digits = digits(number);
+    if digits[0] > 3 {
+        weighted_sum(&digits, &[9, 1, 4, 8, 3, 10, 2, 5, 7, 6, 1]) % 11 == 3
+    } else if number.starts_with("32") {
+        true
+    } else {
+        (1 + weighted_sum(&digits, &[10, 5, 8, 4, 2, 1, 6, 3, 7, 9])) % 11 % 10 == digits[10]
+    }
+}
+
+fn check_mt(number: &str) -> bool {
+    weighted_sum(&digits(number), &[3, 4, 6, 7, 8, 9, 10, 1]).is_multiple_of(37)
+}
+
+/// Accepts both the legacy eleven-test and the ISO 7064 MOD 97-10 scheme used since 2020.
+fn check_nl(number: &str) -> bool {
+    let digits = digits(&number[..9]);
+    let legacy = weighted_sum(&digits, &[9, 8, 7, 6, 5, 4, 3, 2]) % 11;
+    if legacy != 10 && legacy == digits[8] {
+        return true;
+    }
+
+    let remainder = format!("NL{number}").chars().fold(0u64, |acc, c| {
+        let value = c.to_digit(36).expect("pattern checked") as u64;
+        if value < 10 {
+            (acc * 10 + value) % 97
+        } else {
+            (acc * 100 + value) % 97
+        }
+    });
+    remainder == 1
+}
+
+fn check_pl(number: &str) -> bool {
+    let digits = digits(number);
+    let sum = weighted_sum(&digits, &[6, 5, 7, 2, 3, 4, 5, 6, 7]) % 11;
+    sum != 10 && sum == digits[9]
+}
+
+fn check_pt(number: &str) -> bool {
+    let digits = digits(number);
+    let check = 11 - weighted_sum(&digits, &[9, 8, 7, 6, 5, 4, 3, 2]) % 11;
+    (if check >= 10 { 0 } else { check }) == digits[8]
+}
+
+fn check_ro(number: &str) -> bool {
+    let digits = digits(number);
+    let (check, payload) = digits.split_last().expect("pattern checked");
+    let weights = [7, 5, 3, 2, 1, 7, 5, 3, 2];
+    let sum = weighted_sum(payload, &weights[weights.len() - payload.len()..]);
+    (sum * 10) % 11 % 10 == *check
+}
+
+fn check_se(number: &str) -> bool {
+    check_luhn(&number[..10])
+}
+
+fn check_si(number: &str) -> bool {
+    let digits = digits(number);
+    match 11 - weighted_sum(&digits, &[8, 7, 6, 5, 4, 3, 2]) % 11 {
+        11 => false,
+        10 => digits[7] == 0,
+        check => check == digits[7],
+    }
+}
+
+fn check_sk(number: &str) -> bool {
+    number
+        .parse::<u64>()
+        .expect("pattern checked")
+        .is_multiple_of(11)
+}
+
+/// Government departments and health authorities have no check digits.
+fn check_gb(number: &str) -> bool {
+    if number.starts_with("GD") || number.starts_with("HA") {
+        return true;
+    }
+    let digits = digits(&number[..9]);
+    let sum = weighted_sum(&digits, &[8, 7, 6, 5, 4, 3, 2]) + digits[7] * 10 + digits[8];
+    // Residues 0 and 55 are the original schemes, 42 is the "9755" scheme of newer numbers.
+    matches!(sum % 97, 0 | 42 | 55)
+}
+
+fn check_ch(number: &str) -> bool {
+    let digits = digits(number);
+    match 11 - weighted_sum(&digits, &[5, 4, 3, 2, 7, 6, 5, 4]) % 11 {
+        10 => false,
+        11 => digits[8] == 0,
+        check => check == digits[8],
+    }
+}
+
+fn check_no(number: &str) -> bool {
+    let digits = digits(&number[..9]);
+    match 11 - weighted_sum(&digits, &[3, 2, 7, 6, 5, 4, 3, 2]) % 11 {
+        10 => false,
+        11 => digits[8] == 0,
+        check => check == digits[8],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_valid_numbers() {
+        for vat in [
+            "ATU13585627",
+            "BE0411905847",
+            "BG175074752",
+            "BG7523169263",
+            "CY10259033P",
+            "CZ25123891",
+            "CZ7103192745",
+            "DE136695976",
+            "DK13585628",
+            "EE100931558",
+            "EL094014201",
+            "ESA13585625",
+            "ES54362315K",
+            "ESX2482300W",
+            "FI20774740",
+            "FR40303265045",
+            "HR33392005961",
+            "HU21376414",
+            "IE6433435F",
+            "IE3628739UA",
+            "IE8D79739I",
+            "IT00743110157",
+            "LT119511515",
+            "LT100001919017",
+            "LU15027442",
+            "LV40003521600",
+            "LV16117519997",
+            "MT11679112",
+            "NL004495445B01",
+            "NL010000446B01",
+            "PL5260250995",
+            "PT501964843",
+            "RO18547290",
+            "SE556188840401",
+            "SI50223054",
+            "SK2022749619",
+            "GB980780684",
+            "GB100000034",
+            "XI100000034",
+            "CHE-116.281.710 MWST",
+            "NO923609016MVA",
+            "de 136 695 976",
+        ] {
+            assert!(
+                VatNumber::is_valid(vat),
+                "{vat}: {:?}",
+                VatNumber::parse(vat)
+            );
+        }
+    }
+
+    #[test]
+    fn test_wrong_check_digits() {
+        for vat in [
+            "BG175074753",
+            "BG7523169264",
+            "CY10259033Q",
+            "CZ25123892",
+            "CZ7103192746",
+            "ESA13585626",
+            "ES54362315Z",
+            "ESX2482300A",
+            "IE6433435E",
+            "IE3628739VA",
+            "IE8D79739J",
+            "LT119511516",
+            "LT100001919018",
+            "LV40003521601",
+            "LV16117519998",
+            "MT11679113",
+            "GB100000035",
+        ] {
+            assert!(
+                matches!(
+                    VatNumber::parse(vat),
+                    Err(errors::VatError::Checksum { .. })
+                ),
+                "{vat}: {:?}",
+                VatNumber::parse(vat)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            VatNumber::parse("CHE-116.281.710 MWST"),
+            Ok(VatNumber {
+                prefix: "CHE".to_owned(),
+                number: "116281710".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_numbers() {
+        assert_eq!(
+            VatNumber::parse("136695976"),
+            Err(errors::VatError::MissingPrefix)
+        );
+        assert_eq!(
+            VatNumber::parse("GR094014201"),
+            Err(errors::VatError::UnknownCountry {
+                prefix: "GR".to_owned()
+            })
+        );
+        assert_eq!(
+            VatNumber::parse("DE13669597"),
+            Err(errors::VatError::Format {
+                prefix: "DE".to_owned(),
+                expected: "ddddddddd".to_owned()
+            })
+        );
+        assert_eq!(
+            VatNumber::parse("DE136695977"),
+            Err(errors::VatError::Checksum {
+                prefix: "DE".to_owned()
+            })
+        );
+    }
+}
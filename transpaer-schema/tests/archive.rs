@@ -0,0 +1,89 @@
+#![cfg(feature = "archive")]
+
+mod common;
+
+use std::{io::Write, path::PathBuf};
+
+use transpaer_schema::{archive, errors, read};
+
+/// Returns the members of the fixture archives with their contents.
+fn members(dir: &std::path::Path) -> Vec<(&'static str, Vec<u8>)> {
+    let mut members = Vec::new();
+    for name in ["first.yaml", "nested/second.jsonl"] {
+        let path = dir.join(name.replace('/', "-"));
+        common::cataloger(name, &[], &[]).save(&path).unwrap();
+        members.push((name, std::fs::read(path).unwrap()));
+    }
+    members.push(("notes.txt", b"not a substrate".to_vec()));
+    members.push(("broken.jsonl", b"{\"title\": 1}\n".to_vec()));
+    members
+}
+
+fn save_tar_gz(dir: &std::path::Path) -> PathBuf {
+    let path = dir.join("bundle.tar.gz");
+    let file = std::fs::File::create(&path).unwrap();
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for (name, contents) in members(dir) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, &*contents).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+    path
+}
+
+fn save_zip(dir: &std::path::Path) -> PathBuf {
+    let path = dir.join("bundle.zip");
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    for (name, contents) in members(dir) {
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(&contents).unwrap();
+    }
+    writer.finish().unwrap();
+    path
+}
+
+#[test]
+fn read_archive_iterates_substrates_in_bundles() {
+    let dir = common::TempDir::new("archive");
+
+    for path in [save_tar_gz(dir.path()), save_zip(dir.path())] {
+        let members: Vec<_> = archive::read_archive(&path, &read::ReadOptions::default())
+            .unwrap()
+            .collect();
+        assert_eq!(members.len(), 3, "{path:?}");
+
+        for (member, name) in members.iter().zip(["first.yaml", "nested/second.jsonl"]) {
+            let member = member.as_ref().unwrap();
+            assert_eq!(member.archive, path);
+            assert_eq!(member.member, PathBuf::from(name));
+            assert_eq!(member.file.meta.title, name);
+        }
+
+        let err = members[2].as_ref().err().unwrap();
+        assert!(matches!(
+            err,
+            errors::ArchiveError::Member { archive, member, .. }
+                if *archive == path && member.as_os_str() == "broken.jsonl"
+        ));
+        assert!(err.to_string().contains("bundle."), "{err}");
+    }
+}
+
+#[test]
+fn read_archive_rejects_unknown_formats() {
+    let path = std::path::Path::new("bundle.rar");
+    assert!(matches!(
+        archive::read_archive(path, &read::ReadOptions::default()),
+        Err(errors::ArchiveError::UnsupportedFormat { .. })
+    ));
+    assert_eq!(
+        archive::ArchiveFormat::from_path("a/b.tgz".as_ref()),
+        Some(archive::ArchiveFormat::TarGz)
+    );
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use futures_util::StreamExt;
+use transpaer_schema::{self as schema, async_io, read};
+
+fn substrate() -> schema::Substrate {
+    common::producer("async fixture", &["a", "b", "c"])
+}
+
+async fn reviewer_ids(file: async_io::FileStream) -> Vec<String> {
+    let async_io::StreamVariant::Producer(stream) = file.entries else {
+        panic!("expected a producer substrate");
+    };
+    assert_eq!(stream.about().id, "producer");
+    stream
+        .map(|entry| match entry.unwrap() {
+            schema::ProducerEntry::Reviewer(reviewer) => reviewer.id,
+            schema::ProducerEntry::Product(product) => product.id,
+        })
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn save_and_open_file_round_trip() {
+    let dir = common::TempDir::new("async");
+    for extension in ["yaml", "jsonl"] {
+        let path = dir.join(format!("substrate.{extension}"));
+        substrate().save_async(&path).await.unwrap();
+        assert_eq!(
+            read::read_substrate(&path).unwrap().meta,
+            substrate().meta,
+            "{path:?}"
+        );
+
+        let options = read::ReadOptions::default();
+        let file = async_io::open_file(&path, &options).await.unwrap().unwrap();
+        assert_eq!(file.meta, substrate().meta);
+        assert_eq!(reviewer_ids(file).await, ["a", "b", "c"]);
+    }
+}
+
+#[tokio::test]
+async fn read_lines_reports_errors_per_entry() {
+    let lines = [
+        r#"{"title":"async fixture","variant":"producer","version":"0.0.1"}"#,
+        r#"{"id":"producer","ids":{},"name":"Producer","websites":[]}"#,
+        r#"{"type":"reviewer","id":"a","names":["A"]}"#,
+        r#"{"type":"reviewer"}"#,
+    ];
+    let reader = std::io::Cursor::new(lines.join("\n").into_bytes());
+    let options = read::ReadOptions::default();
+    let file = async_io::read_lines(reader, "memory.jsonl", &options)
+        .await
+        .unwrap()
+        .unwrap();
+    let async_io::StreamVariant::Producer(stream) = file.entries else {
+        panic!("expected a producer substrate");
+    };
+    let entries: Vec<_> = stream.collect().await;
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].is_ok());
+    assert!(matches!(
+        &entries[1],
+        Err(schema::errors::ReadError::Json { path, .. }) if path.ends_with("memory.jsonl")
+    ));
+
+    let empty = std::io::Cursor::new(Vec::new());
+    assert!(matches!(
+        async_io::read_lines(empty, "empty.jsonl", &options).await,
+        Err(schema::errors::ReadError::Substrate {
+            source: schema::errors::SubstrateError::NoMeta,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::process::Command;
+
+use transpaer_schema as schema;
+
+fn substrate(products: &[&str]) -> schema::Substrate {
+    common::cataloger("cli fixture", &[], products)
+}
+
+/// Runs the binary and returns its exit code.
+fn run(args: &[&std::ffi::OsStr]) -> i32 {
+    let output = Command::new(env!("CARGO_BIN_EXE_transpaer-schema"))
+        .args(args)
+        .output()
+        .unwrap();
+    output.status.code().unwrap()
+}
+
+#[test]
+fn validate_reports_errors_in_exit_code() {
+    let dir = common::TempDir::new("cli-validate");
+    let good = dir.join("good.yaml");
+    let bad = dir.join("bad.jsonl");
+    substrate(&["a", "b"]).save(&good).unwrap();
+    substrate(&["a", "a"]).save(&bad).unwrap();
+
+    assert_eq!(run(&["validate".as_ref(), good.as_ref()]), 0);
+    assert_eq!(run(&["validate".as_ref(), good.as_ref(), bad.as_ref()]), 1);
+    assert_eq!(
+        run(&["validate".as_ref(), dir.join("missing.yaml").as_ref()]),
+        2
+    );
+}
+
+#[test]
+fn convert_and_diff() {
+    let dir = common::TempDir::new("cli-convert");
+    let yaml = dir.join("substrate.yaml");
+    let jsonl = dir.join("substrate.jsonl");
+    let changed = dir.join("changed.yaml");
+    substrate(&["a", "b"]).save(&yaml).unwrap();
+    substrate(&["a", "c"]).save(&changed).unwrap();
+
+    assert_eq!(run(&["convert".as_ref(), yaml.as_ref(), jsonl.as_ref()]), 0);
+    assert_eq!(run(&["diff".as_ref(), yaml.as_ref(), jsonl.as_ref()]), 0);
+    assert_eq!(run(&["diff".as_ref(), yaml.as_ref(), changed.as_ref()]), 1);
+}
+
+#[test]
+fn merge_combines_entities() {
+    let dir = common::TempDir::new("cli-merge");
+    let first = dir.join("first.yaml");
+    let second = dir.join("second.jsonl");
+    let merged = dir.join("merged.yaml");
+    substrate(&["b", "a"]).save(&first).unwrap();
+    substrate(&["c", "a"]).save(&second).unwrap();
+
+    let args: [&std::ffi::OsStr; 5] = [
+        "merge".as_ref(),
+        first.as_ref(),
+        second.as_ref(),
+        "--output".as_ref(),
+        merged.as_ref(),
+    ];
+    assert_eq!(run(&args), 0);
+    assert_eq!(run(&["sort".as_ref(), merged.as_ref()]), 0);
+
+    let schema::Data::Cataloger(data) = schema::read::read_substrate(&merged).unwrap().data else {
+        panic!("expected a cataloger substrate");
+    };
+    let ids: Vec<_> = data.products.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn stats_prints_fill_rates_as_json() {
+    let dir = common::TempDir::new("cli-stats");
+    let path = dir.join("substrate.jsonl");
+    substrate(&["a", "b"]).save(&path).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_transpaer-schema"))
+        .args(["stats".as_ref(), path.as_os_str(), "--format=json".as_ref()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["products"]["count"], 2);
+    assert_eq!(json[0]["products"]["fields"]["ean"]["fill_rate"], 0.0);
+    assert_eq!(json[0]["producers"]["count"], 0);
+}
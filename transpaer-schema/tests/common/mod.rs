@@ -0,0 +1,137 @@
+//! Fixtures shared by the integration tests.
+
+// Every test binary uses only some of the fixtures.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use transpaer_schema as schema;
+
+/// A fresh directory in the system temporary directory, removed together with its contents when
+/// dropped.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates the directory, the name is made unique per test process.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("transpaer-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.path.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+pub fn meta(title: &str, variant: schema::ProviderVariant) -> schema::Meta {
+    schema::Meta {
+        authors: Vec::new(),
+        creation_timestamp: None,
+        description: None,
+        title: title.to_owned(),
+        valid_from: None,
+        valid_to: None,
+        variant,
+        version: "0.0.1".to_owned(),
+    }
+}
+
+pub fn producer_ids() -> schema::ProducerIds {
+    schema::ProducerIds {
+        domains: None,
+        vat: None,
+        wiki: None,
+    }
+}
+
+/// A producer named after its `id`.
+pub fn catalog_producer(id: &str) -> schema::CatalogProducer {
+    schema::CatalogProducer {
+        description: None,
+        id: id.to_owned(),
+        ids: producer_ids(),
+        images: Vec::new(),
+        names: vec![id.to_uppercase()],
+        origins: None,
+        websites: Vec::new(),
+    }
+}
+
+/// A product with nothing but an `id`.
+pub fn catalog_product(id: &str) -> schema::CatalogProduct {
+    schema::CatalogProduct {
+        availability: None,
+        categorisation: None,
+        description: None,
+        id: id.to_owned(),
+        ids: schema::ProductIds {
+            ean: None,
+            gtin: None,
+            wiki: None,
+        },
+        images: Vec::new(),
+        names: Vec::new(),
+        origins: None,
+        related: None,
+        shopping: None,
+    }
+}
+
+/// A cataloger substrate with the producers and products of the given ids.
+pub fn cataloger(title: &str, producers: &[&str], products: &[&str]) -> schema::Substrate {
+    schema::Substrate {
+        meta: meta(title, schema::ProviderVariant::Cataloger),
+        data: schema::Data::Cataloger(schema::CatalogerData {
+            cataloger: schema::AboutCataloger {
+                description: None,
+                id: "cataloger".to_owned(),
+                name: "Cataloger".to_owned(),
+                variant: schema::CatalogVariant::Database,
+                website: "https://example.com".to_owned(),
+            },
+            producers: producers.iter().map(|id| catalog_producer(id)).collect(),
+            products: products.iter().map(|id| catalog_product(id)).collect(),
+        }),
+    }
+}
+
+/// A producer substrate of the producer with `id` "producer" and the reviewers of the given ids.
+pub fn producer(title: &str, reviewers: &[&str]) -> schema::Substrate {
+    schema::Substrate {
+        meta: meta(title, schema::ProviderVariant::Producer),
+        data: schema::Data::Producer(schema::ProducerData {
+            producer: schema::AboutProducer {
+                description: None,
+                id: "producer".to_owned(),
+                ids: producer_ids(),
+                images: Vec::new(),
+                name: "Producer".to_owned(),
+                origins: None,
+                websites: Vec::new(),
+            },
+            products: Vec::new(),
+            reviewers: reviewers
+                .iter()
+                .map(|id| schema::ProducerReviewer {
+                    description: None,
+                    id: id.to_string(),
+                    names: vec![id.to_uppercase()],
+                })
+                .collect(),
+        }),
+    }
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "dir")]
+
+mod common;
+
+use std::path::Path;
+
+use transpaer_schema::{self as schema, dir, read};
+
+/// Saves substrates of both variants in a directory tree next to an unrelated file.
+fn save(name: &str) -> common::TempDir {
+    let root = common::TempDir::new(&format!("dir-{name}"));
+    std::fs::create_dir_all(root.join("nested")).unwrap();
+    common::cataloger("cataloger fixture", &["a", "b"], &[])
+        .save(&root.join("1.yaml"))
+        .unwrap();
+    common::producer("producer fixture", &["c"])
+        .save(&root.join("2.jsonl"))
+        .unwrap();
+    common::cataloger("cataloger fixture", &["d"], &[])
+        .save(&root.join("nested").join("3.jsonl"))
+        .unwrap();
+    std::fs::write(root.join("README.md"), "not a substrate").unwrap();
+    root
+}
+
+fn ids(entries: dir::SubstrateEntries, root: &Path) -> Vec<(String, String)> {
+    entries
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let id = match entry.entry {
+                dir::AnyEntry::Catalog(schema::CatalogEntry::Producer(p)) => p.id,
+                dir::AnyEntry::Producer(schema::ProducerEntry::Reviewer(r)) => r.id,
+                entry => panic!("unexpected entry: {entry:?}"),
+            };
+            assert!(entry.meta.title.ends_with("fixture"));
+            let path = entry.path.strip_prefix(root).unwrap();
+            (path.to_string_lossy().into_owned(), id)
+        })
+        .collect()
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected
+        .iter()
+        .map(|(path, id)| (path.to_string(), id.to_string()))
+        .collect()
+}
+
+#[test]
+fn read_dir_yields_entries_of_all_substrates() {
+    let root = save("all");
+    let entries = dir::read_dir(root.path(), dir::DirOptions::default()).entries();
+    let nested = format!("nested{}3.jsonl", std::path::MAIN_SEPARATOR);
+    assert_eq!(
+        ids(entries, root.path()),
+        pairs(&[
+            ("1.yaml", "a"),
+            ("1.yaml", "b"),
+            ("2.jsonl", "c"),
+            (&nested, "d")
+        ])
+    );
+
+    let options = dir::DirOptions {
+        max_depth: Some(1),
+        ..Default::default()
+    };
+    let files: Vec<_> = dir::read_dir(root.path(), options)
+        .map(|file| file.unwrap().path)
+        .collect();
+    assert_eq!(files, [root.join("1.yaml"), root.join("2.jsonl")]);
+}
+
+#[test]
+fn read_dir_filters_variants_and_validity() {
+    let root = save("filter");
+    let options = dir::DirOptions {
+        variants: Some(vec![schema::ProviderVariant::Producer]),
+        ..Default::default()
+    };
+    let entries = dir::read_dir(root.path(), options).entries();
+    assert_eq!(ids(entries, root.path()), pairs(&[("2.jsonl", "c")]));
+
+    let mut expired = common::producer("producer fixture", &["e"]);
+    expired.meta.valid_to = Some(schema::chrono::Utc::now() - schema::chrono::Days::new(1));
+    expired.save(&root.join("0.yaml")).unwrap();
+    let options = dir::DirOptions {
+        read: read::ReadOptions::new()
+            .valid_at(schema::chrono::Utc::now())
+            .on_invalid(read::ValidityPolicy::Skip),
+        variants: Some(vec![schema::ProviderVariant::Producer]),
+        max_depth: None,
+    };
+    let entries = dir::read_dir(root.path(), options).entries();
+    assert_eq!(ids(entries, root.path()), pairs(&[("2.jsonl", "c")]));
+}
+
+#[test]
+fn read_paths_reports_errors_and_continues() {
+    let root = save("paths");
+    let paths = vec![
+        root.join("README.md"),
+        root.join("missing.yaml"),
+        root.join("2.jsonl"),
+    ];
+    let results: Vec<_> = dir::read_paths(paths, dir::DirOptions::default())
+        .entries()
+        .collect();
+    assert!(matches!(
+        &results[0],
+        Err(schema::errors::ReadError::Substrate {
+            source: schema::errors::SubstrateError::UnsupportedExtension,
+            path,
+        }) if path.ends_with("README.md")
+    ));
+    assert!(matches!(
+        &results[1],
+        Err(schema::errors::ReadError::Io { path, .. }) if path.ends_with("missing.yaml")
+    ));
+    assert_eq!(results.len(), 3);
+    assert_eq!(&*results[2].as_ref().unwrap().path, root.join("2.jsonl"));
+}
@@ -0,0 +1,344 @@
+mod common;
+
+use transpaer_schema::{self as schema, read};
+
+fn at(day: u32) -> schema::chrono::DateTime<schema::chrono::Utc> {
+    schema::chrono::NaiveDate::from_ymd_opt(2025, 1, day)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+}
+
+fn substrate() -> schema::Substrate {
+    let mut substrate = common::cataloger("read fixture", &[], &[]);
+    substrate.meta.valid_from = Some(at(2));
+    substrate.meta.valid_to = Some(at(4));
+    substrate
+}
+
+/// Saves the fixture in every supported format and returns the paths.
+fn save(dir: &common::TempDir) -> Vec<std::path::PathBuf> {
+    ["yaml", "jsonl"]
+        .into_iter()
+        .map(|extension| {
+            let path = dir.join(format!("substrate.{extension}"));
+            substrate().save(&path).unwrap();
+            path
+        })
+        .collect()
+}
+
+#[test]
+fn open_file_flags_invalid_substrates() {
+    let dir = common::TempDir::new("read-flag");
+    for path in save(&dir) {
+        let mut options = read::ReadOptions::new()
+            .valid_at(at(5))
+            .on_invalid(read::ValidityPolicy::Flag);
+        let file = read::open_file(&path, &options).unwrap().unwrap();
+        assert!(!file.is_valid);
+        assert_eq!(file.meta.title, "read fixture");
+
+        options.valid_at = Some(at(3));
+        let file = read::open_file(&path, &options).unwrap().unwrap();
+        assert!(file.is_valid);
+    }
+}
+
+#[test]
+fn open_file_skips_invalid_substrates() {
+    let dir = common::TempDir::new("read-skip");
+    for path in save(&dir) {
+        let options = read::ReadOptions::new()
+            .valid_at(at(1))
+            .on_invalid(read::ValidityPolicy::Skip);
+        assert!(read::open_file(&path, &options).unwrap().is_none());
+    }
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn open_file_rejects_schema_violations() {
+    let mut substrate = substrate();
+    substrate.meta.variant = schema::ProviderVariant::Reviewer;
+    substrate.data = schema::Data::Reviewer(schema::ReviewerData {
+        reviewer: schema::AboutReviewer {
+            description: "Reviewer".to_owned(),
+            id: "reviewer".to_owned(),
+            name: "Reviewer".to_owned(),
+            reviews: None,
+            website: "https://example.com".to_owned(),
+        },
+        producers: Vec::new(),
+        products: vec![schema::ReviewProduct {
+            availability: None,
+            categorisation: None,
+            id: "product".to_owned(),
+            ids: schema::ProductIds {
+                ean: Some(vec!["5901234123457".to_owned(), "5901234123457".to_owned()]),
+                gtin: None,
+                wiki: None,
+            },
+            images: Vec::new(),
+            names: Vec::new(),
+            origins: None,
+            related: None,
+            reports: None,
+            review: None,
+            shopping: None,
+            summary: None,
+        }],
+    });
+
+    let dir = common::TempDir::new("read-schema");
+    for path in save(&dir) {
+        substrate.save(&path).unwrap();
+
+        let file = read::open_file(&path, &read::ReadOptions::default())
+            .unwrap()
+            .unwrap();
+        let read::FileIterVariant::Review(entries) = file.entries else {
+            panic!("expected a reviewer substrate");
+        };
+        assert!(entries.into_iter().all(|entry| entry.is_ok()));
+
+        let options = read::ReadOptions::new().validate_schema(true);
+        // YAML substrates are checked as a whole, JSON Lines ones entry by entry.
+        let error = match read::open_file(&path, &options) {
+            Err(error) => error,
+            Ok(file) => {
+                let read::FileIterVariant::Review(entries) = file.unwrap().entries else {
+                    panic!("expected a reviewer substrate");
+                };
+                let mut errors: Vec<_> = entries.into_iter().filter_map(Result::err).collect();
+                assert_eq!(errors.len(), 1, "{path:?}");
+                errors.remove(0)
+            }
+        };
+        let schema::errors::ReadError::Schema { violations, .. } = &error else {
+            panic!("expected a schema error, got {error:?}");
+        };
+        assert!(violations
+            .iter()
+            .any(|violation| violation.pointer.ends_with("/ids/ean")));
+    }
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn open_file_rejects_unknown_entry_types() {
+    let dir = common::TempDir::new("read-unknown-type");
+    let path = save(&dir)
+        .into_iter()
+        .find(|path| path.extension().is_some_and(|e| e == "jsonl"))
+        .unwrap();
+    let mut contents = std::fs::read_to_string(&path).unwrap();
+    contents.push_str("{\"type\":\"reviewer\",\"id\":\"r\",\"names\":[]}\n");
+    std::fs::write(&path, contents).unwrap();
+
+    let options = read::ReadOptions::new().validate_schema(true);
+    let file = read::open_file(&path, &options).unwrap().unwrap();
+    let read::FileIterVariant::Catalog(entries) = file.entries else {
+        panic!("expected a cataloger substrate");
+    };
+    let errors: Vec<_> = entries.filter_map(Result::err).collect();
+    assert!(
+        matches!(
+            &errors[..],
+            [schema::errors::ReadError::Schema { violations, .. }]
+                if violations[0].pointer == "/type"
+        ),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn read_delta_round_trips_tombstones() {
+    let base = substrate();
+    let mut new = substrate();
+    new.meta.version = "0.0.2".to_owned();
+    let schema::Data::Cataloger(data) = &mut new.data else {
+        unreachable!()
+    };
+    data.producers.push(common::catalog_producer("producer"));
+    let added = base.delta_to(&new).unwrap();
+    let mut removed = new.delta_to(&base).unwrap();
+    removed.meta.meta.version = "0.0.3".to_owned();
+
+    let dir = common::TempDir::new("read-delta");
+    for extension in ["yaml", "jsonl"] {
+        let added_path = dir.join(format!("added.{extension}"));
+        let removed_path = dir.join(format!("removed.{extension}"));
+        added.save(&added_path).unwrap();
+        removed.save(&removed_path).unwrap();
+        let deltas = [
+            read::read_delta(&added_path, &read::ReadOptions::default())
+                .unwrap()
+                .unwrap(),
+            read::read_delta(&removed_path, &read::ReadOptions::default())
+                .unwrap()
+                .unwrap(),
+        ];
+
+        let folded = base.clone().fold_deltas(&deltas[..1]).unwrap();
+        let schema::Data::Cataloger(data) = &folded.data else {
+            panic!("expected a cataloger substrate");
+        };
+        assert_eq!(data.producers.len(), 1);
+
+        let folded = base.clone().fold_deltas(&deltas).unwrap();
+        let schema::Data::Cataloger(data) = &folded.data else {
+            panic!("expected a cataloger substrate");
+        };
+        assert!(data.producers.is_empty());
+        assert_eq!(folded.meta.version, "0.0.3");
+
+        let mut expired = added.clone();
+        expired.meta.meta.valid_to =
+            Some(schema::chrono::Utc::now() - schema::chrono::Days::new(1));
+        let expired_path = dir.join(format!("expired.{extension}"));
+        expired.save(&expired_path).unwrap();
+        let options = read::ReadOptions::new()
+            .valid_at(schema::chrono::Utc::now())
+            .on_invalid(read::ValidityPolicy::Skip);
+        assert!(read::read_delta(&expired_path, &options).unwrap().is_none());
+    }
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn read_delta_rejects_schema_violations() {
+    let base = substrate();
+    let mut new = substrate();
+    let schema::Data::Cataloger(data) = &mut new.data else {
+        unreachable!()
+    };
+    let mut product = common::catalog_product("product");
+    product.ids.ean = Some(vec!["5901234123457".to_owned(), "5901234123457".to_owned()]);
+    data.products.push(product);
+    let invalid = base.delta_to(&new).unwrap();
+    let tombstones = new.delta_to(&base).unwrap();
+
+    let dir = common::TempDir::new("read-delta-schema");
+    let options = read::ReadOptions::new().validate_schema(true);
+    for extension in ["yaml", "jsonl"] {
+        let invalid_path = dir.join(format!("invalid.{extension}"));
+        let tombstones_path = dir.join(format!("tombstones.{extension}"));
+        invalid.save(&invalid_path).unwrap();
+        tombstones.save(&tombstones_path).unwrap();
+
+        assert!(read::read_delta(&invalid_path, &read::ReadOptions::default()).is_ok());
+        let error = read::read_delta(&invalid_path, &options).unwrap_err();
+        let schema::errors::ReadError::Schema { violations, .. } = &error else {
+            panic!("expected a schema error, got {error:?}");
+        };
+        assert!(violations
+            .iter()
+            .any(|violation| violation.pointer.ends_with("/ids/ean")));
+
+        assert!(read::read_delta(&tombstones_path, &options)
+            .unwrap()
+            .is_some());
+    }
+}
+
+#[test]
+fn products_matching_streams_filtered_products() {
+    let mut substrate = substrate();
+    let schema::Data::Cataloger(data) = &mut substrate.data else {
+        unreachable!()
+    };
+    for (id, category) in [("a", "food"), ("b", "toys"), ("c", "food")] {
+        data.products.push(schema::CatalogProduct {
+            categorisation: Some(schema::ProductCategorisation {
+                categories: vec![schema::ProductCategory(category.to_owned())],
+            }),
+            ..common::catalog_product(id)
+        });
+    }
+
+    let dir = common::TempDir::new("read-query");
+    for extension in ["yaml", "jsonl"] {
+        let path = dir.join(format!("substrate.{extension}"));
+        substrate.save(&path).unwrap();
+        let read::FileIterVariant::Catalog(iter) = read::iter_file(&path).unwrap() else {
+            panic!("expected a cataloger substrate");
+        };
+        let ids: Vec<String> = iter
+            .products_matching(schema::query::Filter::in_category("food"))
+            .map(|product| product.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["a", "c"]);
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn open_file_parses_lines_in_parallel() {
+    let dir = common::TempDir::new("read-parallel");
+    let path = dir.join("substrate.jsonl");
+    let mut substrate = substrate();
+    let schema::Data::Cataloger(data) = &mut substrate.data else {
+        unreachable!()
+    };
+    let expected: Vec<String> = (0..1000).map(|i| format!("product-{i}")).collect();
+    for id in &expected {
+        data.products.push(common::catalog_product(id));
+    }
+    substrate.save(&path).unwrap();
+
+    let read_ids = |ordered| {
+        let options = read::ReadOptions::new().parallel(read::ParallelOptions {
+            batch_size: 64,
+            ordered,
+        });
+        let file = read::open_file(&path, &options).unwrap().unwrap();
+        let read::FileIterVariant::Catalog(iter) = file.entries else {
+            panic!("expected a cataloger substrate");
+        };
+        iter.map(|entry| match entry.unwrap() {
+            schema::CatalogEntry::Product(product) => product.id,
+            schema::CatalogEntry::Producer(producer) => producer.id,
+        })
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(read_ids(true), expected);
+    let mut unordered = read_ids(false);
+    unordered.sort_by_key(|id| id[8..].parse::<usize>().unwrap());
+    assert_eq!(unordered, expected);
+
+    // Consuming the entries on the thread pool used for parsing them must not deadlock.
+    let options = read::ReadOptions::new().parallel(read::ParallelOptions {
+        batch_size: 8,
+        ordered: false,
+    });
+    let file = read::open_file(&path, &options).unwrap().unwrap();
+    let read::FileIterVariant::Catalog(iter) = file.entries else {
+        panic!("expected a cataloger substrate");
+    };
+    let count = rayon::iter::ParallelIterator::count(rayon::iter::ParallelBridge::par_bridge(
+        iter.map(|entry| {
+            std::thread::sleep(std::time::Duration::from_micros(100));
+            entry.unwrap()
+        }),
+    ));
+    assert_eq!(count, expected.len());
+
+    let mut contents = std::fs::read_to_string(&path).unwrap();
+    contents.push_str("{\"type\":\"product\"}\n");
+    std::fs::write(&path, contents).unwrap();
+    let options = read::ReadOptions::new().parallel(read::ParallelOptions::default());
+    let file = read::open_file(&path, &options).unwrap().unwrap();
+    let read::FileIterVariant::Catalog(iter) = file.entries else {
+        panic!("expected a cataloger substrate");
+    };
+    let entries: Vec<_> = iter.collect();
+    assert_eq!(entries.len(), 1001);
+    assert!(entries[..1000].iter().all(Result::is_ok));
+    assert!(matches!(
+        entries[1000],
+        Err(schema::errors::ReadError::Json { .. })
+    ));
+}
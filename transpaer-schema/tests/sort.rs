@@ -0,0 +1,363 @@
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+use transpaer_schema as schema;
+
+fn strings() -> impl Strategy<Value = Vec<String>> {
+    prop::collection::vec("[a-c]{0,2}", 0..4)
+}
+
+fn optional_strings() -> impl Strategy<Value = Option<Vec<String>>> {
+    prop::option::of(strings())
+}
+
+fn product_ids() -> impl Strategy<Value = schema::ProductIds> {
+    (optional_strings(), optional_strings(), optional_strings())
+        .prop_map(|(ean, gtin, wiki)| schema::ProductIds { ean, gtin, wiki })
+}
+
+fn producer_ids() -> impl Strategy<Value = schema::ProducerIds> {
+    (optional_strings(), optional_strings(), optional_strings())
+        .prop_map(|(domains, vat, wiki)| schema::ProducerIds { domains, vat, wiki })
+}
+
+fn region_list() -> impl Strategy<Value = schema::RegionList> {
+    strings().prop_map(schema::RegionList)
+}
+
+fn regions() -> impl Strategy<Value = schema::Regions> {
+    prop_oneof![
+        Just(schema::Regions::Variant(schema::RegionVariant::All)),
+        Just(schema::Regions::Variant(schema::RegionVariant::Unknown)),
+        region_list().prop_map(schema::Regions::List),
+    ]
+}
+
+fn product_origins() -> impl Strategy<Value = schema::ProductOrigins> {
+    (strings(), prop::option::of(region_list())).prop_map(|(producer_ids, regions)| {
+        schema::ProductOrigins {
+            producer_ids,
+            regions,
+        }
+    })
+}
+
+fn producer_origins() -> impl Strategy<Value = schema::ProducerOrigins> {
+    prop::option::of(region_list()).prop_map(|regions| schema::ProducerOrigins { regions })
+}
+
+fn related() -> impl Strategy<Value = schema::RelatedProducts> {
+    (optional_strings(), optional_strings()).prop_map(|(followed_by, preceded_by)| {
+        schema::RelatedProducts {
+            followed_by,
+            preceded_by,
+        }
+    })
+}
+
+fn categorisation() -> impl Strategy<Value = schema::ProductCategorisation> {
+    strings().prop_map(|categories| schema::ProductCategorisation {
+        categories: categories
+            .into_iter()
+            .map(schema::ProductCategory)
+            .collect(),
+    })
+}
+
+fn shopping() -> impl Strategy<Value = schema::Shopping> {
+    let shop = prop_oneof![
+        Just(schema::VerifiedShop::Amazon),
+        Just(schema::VerifiedShop::Fairphone)
+    ];
+    prop::collection::vec((shop, "[a-c]{0,2}", "[a-c]{0,2}"), 0..4).prop_map(|entries| {
+        schema::Shopping(
+            entries
+                .into_iter()
+                .map(|(shop, id, description)| schema::ShoppingEntry {
+                    shop,
+                    id,
+                    description,
+                })
+                .collect(),
+        )
+    })
+}
+
+fn reports() -> impl Strategy<Value = schema::Reports> {
+    let report = (
+        prop::option::of("[a-c]{0,2}"),
+        prop::option::of("[a-c]{0,2}"),
+    )
+        .prop_map(|(title, url)| schema::Report { title, url });
+    prop::collection::vec(report, 0..4).prop_map(schema::Reports)
+}
+
+fn catalog_product() -> impl Strategy<Value = schema::CatalogProduct> {
+    (
+        prop::option::of(regions()),
+        prop::option::of(categorisation()),
+        product_ids(),
+        strings(),
+        strings(),
+        prop::option::of(product_origins()),
+        prop::option::of(related()),
+        prop::option::of(shopping()),
+    )
+        .prop_map(
+            |(availability, categorisation, ids, images, names, origins, related, shopping)| {
+                schema::CatalogProduct {
+                    availability: availability
+                        .map(|regions| schema::ProductAvailability { regions }),
+                    categorisation,
+                    description: None,
+                    id: String::new(),
+                    ids,
+                    images,
+                    names,
+                    origins,
+                    related,
+                    shopping,
+                }
+            },
+        )
+}
+
+fn catalog_producer() -> impl Strategy<Value = schema::CatalogProducer> {
+    (
+        producer_ids(),
+        strings(),
+        strings(),
+        prop::option::of(producer_origins()),
+        strings(),
+    )
+        .prop_map(
+            |(ids, images, names, origins, websites)| schema::CatalogProducer {
+                description: None,
+                id: String::new(),
+                ids,
+                images,
+                names,
+                origins,
+                websites,
+            },
+        )
+}
+
+fn review_product() -> impl Strategy<Value = schema::ReviewProduct> {
+    (catalog_product(), prop::option::of(reports())).prop_map(|(product, reports)| {
+        schema::ReviewProduct {
+            availability: product.availability,
+            categorisation: product.categorisation,
+            id: product.id,
+            ids: product.ids,
+            images: product.images,
+            names: product.names,
+            origins: product.origins,
+            related: product.related,
+            reports,
+            review: None,
+            shopping: product.shopping,
+            summary: None,
+        }
+    })
+}
+
+fn review_producer() -> impl Strategy<Value = schema::ReviewProducer> {
+    (catalog_producer(), prop::option::of(reports())).prop_map(|(producer, reports)| {
+        schema::ReviewProducer {
+            description: producer.description,
+            id: producer.id,
+            ids: producer.ids,
+            images: producer.images,
+            names: producer.names,
+            origins: producer.origins,
+            reports,
+            review: None,
+            websites: producer.websites,
+        }
+    })
+}
+
+/// Sets the `id`s from a small pool, so that some entities share them.
+fn with_ids<T>(
+    entities: Vec<(T, String)>,
+    prefix: &str,
+    id: impl Fn(&mut T) -> &mut String,
+) -> Vec<T> {
+    entities
+        .into_iter()
+        .map(|(mut entity, suffix)| {
+            *id(&mut entity) = format!("{prefix}-{suffix}");
+            entity
+        })
+        .collect()
+}
+
+fn entities<T: std::fmt::Debug>(
+    entity: impl Strategy<Value = T>,
+) -> impl Strategy<Value = Vec<(T, String)>> {
+    prop::collection::vec((entity, "[a-b]"), 0..4)
+}
+
+fn producer_product() -> impl Strategy<Value = schema::ProducerProduct> {
+    (catalog_product(), categorisation(), "[a-c]{0,2}").prop_map(
+        |(product, categorisation, description)| schema::ProducerProduct {
+            availability: product.availability,
+            categorisation,
+            description,
+            id: product.id,
+            ids: product.ids,
+            images: product.images,
+            names: product.names,
+            origins: product.origins,
+            related: product.related,
+            shopping: product.shopping,
+        },
+    )
+}
+
+fn producer_reviewer() -> impl Strategy<Value = schema::ProducerReviewer> {
+    (prop::option::of("[a-c]{0,2}"), strings()).prop_map(|(description, names)| {
+        schema::ProducerReviewer {
+            description,
+            id: String::new(),
+            names,
+        }
+    })
+}
+
+fn cataloger_data() -> impl Strategy<Value = schema::CatalogerData> {
+    (entities(catalog_producer()), entities(catalog_product())).prop_map(|(producers, products)| {
+        schema::CatalogerData {
+            cataloger: schema::AboutCataloger {
+                description: None,
+                id: "cataloger".to_owned(),
+                name: "Cataloger".to_owned(),
+                variant: schema::CatalogVariant::Database,
+                website: "https://example.com".to_owned(),
+            },
+            producers: with_ids(producers, "producer", |p| &mut p.id),
+            products: with_ids(products, "product", |p| &mut p.id),
+        }
+    })
+}
+
+fn reviewer_data() -> impl Strategy<Value = schema::ReviewerData> {
+    (entities(review_producer()), entities(review_product())).prop_map(|(producers, products)| {
+        schema::ReviewerData {
+            producers: with_ids(producers, "producer", |p| &mut p.id),
+            products: with_ids(products, "product", |p| &mut p.id),
+            reviewer: schema::AboutReviewer {
+                description: "Reviewer".to_owned(),
+                id: "reviewer".to_owned(),
+                name: "Reviewer".to_owned(),
+                reviews: None,
+                website: "https://example.com".to_owned(),
+            },
+        }
+    })
+}
+
+fn producer_data() -> impl Strategy<Value = schema::ProducerData> {
+    (
+        catalog_producer(),
+        entities(producer_product()),
+        entities(producer_reviewer()),
+    )
+        .prop_map(|(producer, products, reviewers)| schema::ProducerData {
+            producer: schema::AboutProducer {
+                description: None,
+                id: "producer".to_owned(),
+                ids: producer.ids,
+                images: producer.images,
+                name: "Producer".to_owned(),
+                origins: producer.origins,
+                websites: producer.websites,
+            },
+            products: with_ids(products, "product", |p| &mut p.id),
+            reviewers: with_ids(reviewers, "reviewer", |r| &mut r.id),
+        })
+}
+
+/// Permutes every array in the value using a simple linear congruential generator.
+fn shuffle_json(value: &mut serde_json::Value, seed: &mut u64) {
+    match value {
+        serde_json::Value::Array(items) => {
+            for i in (1..items.len()).rev() {
+                *seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let j = ((*seed >> 33) % (i as u64 + 1)) as usize;
+                items.swap(i, j);
+            }
+            for item in items {
+                shuffle_json(item, seed);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                shuffle_json(item, seed);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn shuffled<T>(value: &T, mut seed: u64) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let mut json = serde_json::to_value(value).unwrap();
+    shuffle_json(&mut json, &mut seed);
+    serde_json::from_value(json).unwrap()
+}
+
+fn sorted<T: Clone>(value: &T, sort: impl Fn(&mut T)) -> T {
+    let mut value = value.clone();
+    sort(&mut value);
+    value
+}
+
+proptest! {
+    #[test]
+    fn sort_cataloger_data_is_idempotent(data in cataloger_data()) {
+        let once = sorted(&data, schema::CatalogerData::sort);
+        let twice = sorted(&once, schema::CatalogerData::sort);
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn sort_cataloger_data_ignores_input_order(data in cataloger_data(), seed in any::<u64>()) {
+        let expected = sorted(&data, schema::CatalogerData::sort);
+        let received = sorted(&shuffled(&data, seed), schema::CatalogerData::sort);
+        assert_eq!(expected, received);
+    }
+
+    #[test]
+    fn sort_producer_data_is_idempotent(data in producer_data()) {
+        let once = sorted(&data, schema::ProducerData::sort);
+        let twice = sorted(&once, schema::ProducerData::sort);
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn sort_producer_data_ignores_input_order(data in producer_data(), seed in any::<u64>()) {
+        let expected = sorted(&data, schema::ProducerData::sort);
+        let received = sorted(&shuffled(&data, seed), schema::ProducerData::sort);
+        assert_eq!(expected, received);
+    }
+
+    #[test]
+    fn sort_reviewer_data_is_idempotent(data in reviewer_data()) {
+        let once = sorted(&data, schema::ReviewerData::sort);
+        let twice = sorted(&once, schema::ReviewerData::sort);
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn sort_reviewer_data_ignores_input_order(data in reviewer_data(), seed in any::<u64>()) {
+        let expected = sorted(&data, schema::ReviewerData::sort);
+        let received = sorted(&shuffled(&data, seed), schema::ReviewerData::sort);
+        assert_eq!(expected, received);
+    }
+}